@@ -0,0 +1,132 @@
+//! Writes the same key set split into batches of growing size, possibly several
+//! batches at a time, to find the best batch size of each backend.
+
+use anyhow::Result;
+use futures::{StreamExt, TryStreamExt};
+use linera_base::time::Instant;
+use linera_views::{
+    batch::Batch,
+    store::{KeyValueStore, ReadableKeyValueStore, TestKeyValueDatabase, WritableKeyValueStore},
+};
+use rand::SeedableRng;
+
+use crate::{get_key, measure, sweep::SizeList, Parameters, Report, Settings};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct BatchScaling {
+    /// Batch sizes of the batch-write operation. Defaults to 1, 10, 100, ... up to `num_key`.
+    #[arg(long, value_parser = SizeList::parse_positive)]
+    pub batch_sizes: Option<SizeList>,
+
+    /// Number of batches written at the same time by the batch-write operation.
+    /// Accepts a list, e.g. `1,4,16`.
+    #[arg(long, default_value = "1", value_parser = SizeList::parse_positive)]
+    pub batch_parallelism: SizeList,
+}
+
+impl BatchScaling {
+    fn batch_sizes(&self, num_key: usize) -> Vec<usize> {
+        if let Some(batch_sizes) = &self.batch_sizes {
+            return batch_sizes.0.clone();
+        }
+        let mut batch_sizes = Vec::new();
+        let mut batch_size = 1;
+        while batch_size < num_key {
+            batch_sizes.push(batch_size);
+            batch_size *= 10;
+        }
+        batch_sizes.push(num_key.max(1));
+        batch_sizes
+    }
+}
+
+pub async fn test_storage_batch_write_kernel<S>(
+    name: &str,
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore,
+{
+    let mut rng = rand::rngs::StdRng::seed_from_u64(134 as u64);
+    let mut key_values = Vec::new();
+    for _ in 0..parameters.num_key {
+        let key = get_key(&mut rng, parameters.key_size);
+        let value = get_key(&mut rng, parameters.value_size);
+        key_values.push((key, value));
+    }
+    let keys = key_values
+        .iter()
+        .map(|(key, _)| key.clone())
+        .collect::<Vec<_>>();
+    let expected = key_values
+        .iter()
+        .map(|(_, value)| Some(value.clone()))
+        .collect::<Vec<_>>();
+    let scaling = &settings.batch_scaling;
+
+    for batch_size in scaling.batch_sizes(parameters.num_key) {
+        for &parallelism in &scaling.batch_parallelism.0 {
+            let result = measure(settings.repetitions, || {
+                let (key_values, keys, expected) = (key_values.clone(), keys.clone(), &expected);
+                async move {
+                    let store = S::new_test_store().await?;
+                    let batches = key_values
+                        .chunks(batch_size)
+                        .map(|chunk| {
+                            let mut batch = Batch::new();
+                            for (key, value) in chunk {
+                                batch.put_key_value_bytes(key.clone(), value.clone());
+                            }
+                            batch
+                        })
+                        .collect::<Vec<_>>();
+                    let time = Instant::now();
+                    futures::stream::iter(batches)
+                        .map(|batch| store.write_batch(batch))
+                        .buffer_unordered(parallelism)
+                        .try_collect::<Vec<_>>()
+                        .await?;
+                    let elapsed = time.elapsed();
+                    let values: Vec<Option<Vec<u8>>> = store.read_multi_values_bytes(keys).await?;
+                    assert_eq!(&values, expected);
+                    Ok::<_, anyhow::Error>(elapsed)
+                }
+            })
+            .await;
+            let strategy = format!("batch{batch_size}-p{parallelism}");
+            match result {
+                Ok(statistics) => {
+                    report.add(parameters.record(name, "batch-write", &strategy, statistics))
+                }
+                // A backend refusing a batch size (e.g. a transaction item cap) is a result, not an abort.
+                Err(error) => {
+                    report.add_failure(format!("{name} for {strategy} batch-write: {error:#}"))
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::Args;
+
+    fn parse(options: &[&str]) -> Result<Args, clap::Error> {
+        Args::try_parse_from(["benchmark", "100", "4", "8"].iter().chain(options))
+    }
+
+    #[test]
+    fn test_batch_options_are_positive() {
+        let args = parse(&["--batch-sizes", "1,10", "--batch-parallelism", "1,4"]).unwrap();
+        assert_eq!(args.batch_scaling.batch_sizes.unwrap().0, [1, 10]);
+        assert_eq!(args.batch_scaling.batch_parallelism.0, [1, 4]);
+        assert!(parse(&["--batch-sizes", "0,10"]).is_err());
+        assert!(parse(&["--batch-parallelism", "0"]).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Comparison of a run against a baseline saved with `--save-baseline`.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::report::{BenchmarkRecord, Report};
+
+/// Saves the records as JSON, to be used later with `--compare`.
+pub fn save_baseline(path: &Path, records: &[BenchmarkRecord]) -> Result<()> {
+    let content = serde_json::to_string_pretty(records)?;
+    std::fs::write(path, content)
+        .with_context(|| format!("failed to write the baseline {}", path.display()))
+}
+
+fn read_baseline(path: &Path) -> Result<Vec<BenchmarkRecord>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read the baseline {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("failed to parse the baseline {}", path.display()))
+}
+
+/// How a median moved with respect to the one of the baseline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Change {
+    /// Went up by more than the threshold, with the change in percent.
+    Regression(f64),
+    /// Went down by more than the threshold, with the change in percent.
+    Improvement(f64),
+    /// Moved by at most the threshold, with the change in percent.
+    Stable(f64),
+    /// The baseline median is zero, e.g. a fast operation rounded to 0 micros, so that
+    /// no relative change can be computed.
+    ZeroBaseline,
+}
+
+fn compare_medians(reference: f64, median: f64, threshold: f64) -> Change {
+    if reference == 0.0 {
+        return Change::ZeroBaseline;
+    }
+    let change = (median - reference) / reference * 100.0;
+    if change > threshold {
+        Change::Regression(change)
+    } else if change < -threshold {
+        Change::Improvement(change)
+    } else {
+        Change::Stable(change)
+    }
+}
+
+/// Compares the medians of the report with the ones of the baseline and returns the
+/// number of regressions, i.e. of medians that went up by more than `threshold` percent.
+/// A zero baseline median is only reported with the absolute difference.
+pub fn compare_with_baseline(path: &Path, threshold: f64, report: &Report) -> Result<usize> {
+    let baseline = read_baseline(path)?;
+    let mut num_regressions = 0;
+    report.log(&format!(
+        "Comparison with the baseline {} (threshold {threshold}%)",
+        path.display()
+    ));
+    for record in report.records() {
+        let Some(reference) = baseline.iter().find(|other| other.same_measure(record)) else {
+            report.log(&format!("    NEW        {}", record.description()));
+            continue;
+        };
+        let (reference, median) = (reference.statistics.median, record.statistics.median);
+        let (status, change) = match compare_medians(reference, median, threshold) {
+            Change::Regression(change) => {
+                num_regressions += 1;
+                ("REGRESSION", format!("{change:+.1}%"))
+            }
+            Change::Improvement(change) => ("IMPROVED", format!("{change:+.1}%")),
+            Change::Stable(change) => ("ok", format!("{change:+.1}%")),
+            Change::ZeroBaseline => ("ZERO-BASE", format!("{:+.0} micros", median - reference)),
+        };
+        report.log(&format!(
+            "    {status:<10} {}: median {reference:.0} -> {median:.0} micros ({change})",
+            record.description(),
+        ));
+    }
+    Ok(num_regressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_medians() {
+        assert_eq!(compare_medians(50.0, 75.0, 10.0), Change::Regression(50.0));
+        assert_eq!(
+            compare_medians(50.0, 25.0, 10.0),
+            Change::Improvement(-50.0)
+        );
+        assert_eq!(compare_medians(50.0, 50.0, 10.0), Change::Stable(0.0));
+        assert!(matches!(
+            compare_medians(100.0, 105.0, 10.0),
+            Change::Stable(_)
+        ));
+        assert!(matches!(
+            compare_medians(100.0, 95.0, 10.0),
+            Change::Stable(_)
+        ));
+    }
+
+    #[test]
+    fn test_compare_with_a_zero_median() {
+        assert_eq!(compare_medians(0.0, 3.0, 10.0), Change::ZeroBaseline);
+        assert_eq!(compare_medians(0.0, 0.0, 10.0), Change::ZeroBaseline);
+        assert_eq!(compare_medians(4.0, 0.0, 10.0), Change::Improvement(-100.0));
+    }
+}
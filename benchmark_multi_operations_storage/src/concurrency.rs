@@ -0,0 +1,99 @@
+use std::future::Future;
+
+use futures::{StreamExt, TryStreamExt};
+
+/// Awaits all the futures, with at most `limit` of them in flight if given, and
+/// returns their outputs in order. Without a limit, all the requests are sent at once.
+/// A limit must be positive, which `--max-in-flight` checks.
+pub async fn try_join_limited<I, F, T, E>(futures: I, limit: Option<usize>) -> Result<Vec<T>, E>
+where
+    I: IntoIterator<Item = F>,
+    F: Future<Output = Result<T, E>>,
+{
+    let Some(limit) = limit else {
+        return futures::future::try_join_all(futures).await;
+    };
+    let mut outputs = futures::stream::iter(
+        futures
+            .into_iter()
+            .enumerate()
+            .map(|(index, future)| async move { future.await.map(|output| (index, output)) }),
+    )
+    .buffer_unordered(limit)
+    .try_collect::<Vec<_>>()
+    .await?;
+    outputs.sort_by_key(|(index, _)| *index);
+    Ok(outputs.into_iter().map(|(_, output)| output).collect())
+}
+
+/// The name of the futures strategy with the given in-flight limit.
+pub fn futures_strategy(limit: Option<usize>) -> String {
+    match limit {
+        None => "futures".to_string(),
+        Some(limit) => format!("futures-k{limit}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use clap::Parser;
+
+    use super::*;
+    use crate::Args;
+
+    #[tokio::test]
+    async fn test_try_join_limited() {
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+        let futures = (0..10).map(|index| {
+            let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+            async move {
+                let count = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(count, Ordering::SeqCst);
+                // Later futures finish first, so that the outputs must be reordered.
+                for _ in 0..10 - index {
+                    tokio::task::yield_now().await;
+                }
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok::<_, ()>(index)
+            }
+        });
+        assert_eq!(
+            try_join_limited(futures, Some(3)).await,
+            Ok((0..10).collect())
+        );
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+
+        let futures = (0..5).map(|index| async move { Ok::<_, ()>(index) });
+        assert_eq!(
+            try_join_limited(futures, None).await,
+            Ok(vec![0, 1, 2, 3, 4])
+        );
+        let futures = (0..5).map(|index| async move {
+            if index == 3 {
+                Err(index)
+            } else {
+                Ok(index)
+            }
+        });
+        assert_eq!(try_join_limited(futures, Some(2)).await, Err(3));
+    }
+
+    #[test]
+    fn test_max_in_flight_is_positive() {
+        let parse =
+            |limits| Args::try_parse_from(["benchmark", "1", "1", "1", "--max-in-flight", limits]);
+        let args = parse("1,4").unwrap();
+        assert_eq!(args.max_in_flight.unwrap().0, [1, 4]);
+        assert!(parse("0").is_err());
+        assert!(parse("4,0").is_err());
+    }
+
+    #[test]
+    fn test_futures_strategy() {
+        assert_eq!(futures_strategy(None), "futures");
+        assert_eq!(futures_strategy(Some(8)), "futures-k8");
+    }
+}
@@ -0,0 +1,475 @@
+//! Kernels for the prefix scans, prefix deletions and key lookups. As for the read
+//! and write kernels, every operation is done with a single multi-key call (`multi`),
+//! with sequential single-key calls (`loop`) and with concurrent single-key calls (`futures`).
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use linera_base::time::Instant;
+use linera_views::{
+    batch::Batch,
+    store::{KeyValueStore, ReadableKeyValueStore, TestKeyValueDatabase, WritableKeyValueStore},
+};
+use rand::SeedableRng;
+
+use crate::{
+    concurrency::{futures_strategy, try_join_limited},
+    get_key, measure, Parameters, Report, Settings,
+};
+
+/// First byte of every key written by the kernels.
+const KEY_PREFIX: u8 = 1;
+
+/// First byte of the keys that are looked up but never written.
+const ABSENT_KEY_PREFIX: u8 = 2;
+
+/// Random key-values starting with `prefix`, sorted and without duplicates, as the stores
+/// return them. A key drawn twice is only kept once, since its second value would
+/// overwrite the first one.
+pub fn get_key_values(parameters: Parameters, prefix: &[u8]) -> BTreeMap<Vec<u8>, Vec<u8>> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(134 as u64);
+    let mut key_values = BTreeMap::new();
+    for _ in 0..parameters.num_key {
+        let mut key = prefix.to_vec();
+        key.extend(get_key(&mut rng, parameters.key_size));
+        let value = get_key(&mut rng, parameters.value_size);
+        key_values.insert(key, value);
+    }
+    key_values
+}
+
+/// The key-values of the data set of the kernels.
+fn get_prefixed_key_values(parameters: Parameters) -> BTreeMap<Vec<u8>, Vec<u8>> {
+    get_key_values(parameters, &[KEY_PREFIX])
+}
+
+async fn new_filled_store<S>(key_values: &BTreeMap<Vec<u8>, Vec<u8>>) -> Result<S::Store>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore,
+{
+    let store = S::new_test_store().await?;
+    let mut batch = Batch::new();
+    for (key, value) in key_values {
+        batch.put_key_value_bytes(key.clone(), value.clone());
+    }
+    store.write_batch(batch).await?;
+    Ok(store)
+}
+
+pub async fn test_storage_find_keys_kernel<S>(
+    name: &str,
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore,
+{
+    let key_values = get_prefixed_key_values(parameters);
+    let keys = key_values.keys().cloned().collect::<Vec<_>>();
+    let suffixes = keys.iter().map(|key| key[1..].to_vec()).collect::<Vec<_>>();
+    let store = new_filled_store::<S>(&key_values).await?;
+    //
+    let statistics = measure(settings.repetitions, || {
+        let (store, suffixes) = (store.clone(), &suffixes);
+        async move {
+            let time = Instant::now();
+            let found: Vec<Vec<u8>> = store.find_keys_by_prefix(&[KEY_PREFIX]).await?;
+            let elapsed = time.elapsed();
+            assert_eq!(&found, suffixes);
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "find-keys", "multi", statistics));
+    //
+    let statistics = measure(settings.repetitions, || {
+        let (store, keys) = (store.clone(), keys.clone());
+        async move {
+            let time = Instant::now();
+            let mut results = Vec::new();
+            for key in keys {
+                results.push(store.find_keys_by_prefix(&key).await?);
+            }
+            let elapsed = time.elapsed();
+            assert!(results.iter().all(|found| found == &vec![Vec::<u8>::new()]));
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "find-keys", "loop", statistics));
+    //
+    for &limit in &settings.in_flight_limits {
+        let statistics = measure(settings.repetitions, || {
+            let (store, keys) = (store.clone(), keys.clone());
+            async move {
+                let time = Instant::now();
+                let mut futures = Vec::new();
+                for key in keys {
+                    let store = store.clone();
+                    futures.push(async move { store.find_keys_by_prefix(&key).await });
+                }
+                let results: Vec<Vec<Vec<u8>>> = try_join_limited(futures, limit).await?;
+                let elapsed = time.elapsed();
+                assert!(results.iter().all(|found| found == &vec![Vec::<u8>::new()]));
+                Ok::<_, anyhow::Error>(elapsed)
+            }
+        })
+        .await?;
+        report.add(parameters.record(name, "find-keys", &futures_strategy(limit), statistics));
+    }
+    Ok(())
+}
+
+pub async fn test_storage_find_key_values_kernel<S>(
+    name: &str,
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore,
+{
+    let key_values = get_prefixed_key_values(parameters);
+    let keys = key_values.keys().cloned().collect::<Vec<_>>();
+    let expected = key_values
+        .iter()
+        .map(|(key, value)| (key[1..].to_vec(), value.clone()))
+        .collect::<Vec<_>>();
+    let single_expected = key_values
+        .values()
+        .map(|value| vec![(Vec::new(), value.clone())])
+        .collect::<Vec<_>>();
+    let store = new_filled_store::<S>(&key_values).await?;
+    //
+    let statistics = measure(settings.repetitions, || {
+        let (store, expected) = (store.clone(), &expected);
+        async move {
+            let time = Instant::now();
+            let found: Vec<(Vec<u8>, Vec<u8>)> =
+                store.find_key_values_by_prefix(&[KEY_PREFIX]).await?;
+            let elapsed = time.elapsed();
+            assert_eq!(&found, expected);
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "find-key-values", "multi", statistics));
+    //
+    let statistics = measure(settings.repetitions, || {
+        let (store, keys, single_expected) = (store.clone(), keys.clone(), &single_expected);
+        async move {
+            let time = Instant::now();
+            let mut results = Vec::new();
+            for key in keys {
+                results.push(store.find_key_values_by_prefix(&key).await?);
+            }
+            let elapsed = time.elapsed();
+            assert_eq!(&results, single_expected);
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "find-key-values", "loop", statistics));
+    //
+    for &limit in &settings.in_flight_limits {
+        let statistics = measure(settings.repetitions, || {
+            let (store, keys, single_expected) = (store.clone(), keys.clone(), &single_expected);
+            async move {
+                let time = Instant::now();
+                let mut futures = Vec::new();
+                for key in keys {
+                    let store = store.clone();
+                    futures.push(async move { store.find_key_values_by_prefix(&key).await });
+                }
+                let results: Vec<Vec<(Vec<u8>, Vec<u8>)>> =
+                    try_join_limited(futures, limit).await?;
+                let elapsed = time.elapsed();
+                assert_eq!(&results, single_expected);
+                Ok::<_, anyhow::Error>(elapsed)
+            }
+        })
+        .await?;
+        report.add(parameters.record(
+            name,
+            "find-key-values",
+            &futures_strategy(limit),
+            statistics,
+        ));
+    }
+    Ok(())
+}
+
+pub async fn test_storage_delete_prefix_kernel<S>(
+    name: &str,
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore,
+{
+    let key_values = get_prefixed_key_values(parameters);
+    let keys = key_values.keys().cloned().collect::<Vec<_>>();
+    //
+    // Every run deletes the whole data set, so it starts from a freshly filled store.
+    //
+    let statistics = measure(settings.repetitions, || {
+        let (key_values, keys) = (&key_values, keys.clone());
+        async move {
+            let store = new_filled_store::<S>(key_values).await?;
+            let time = Instant::now();
+            let mut batch = Batch::new();
+            for key in keys {
+                batch.delete_key_prefix(key);
+            }
+            store.write_batch(batch).await?;
+            let elapsed = time.elapsed();
+            assert!(store.find_keys_by_prefix(&[KEY_PREFIX]).await?.is_empty());
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "delete-prefix", "multi", statistics));
+    //
+    let statistics = measure(settings.repetitions, || {
+        let (key_values, keys) = (&key_values, keys.clone());
+        async move {
+            let store = new_filled_store::<S>(key_values).await?;
+            let time = Instant::now();
+            for key in keys {
+                let mut batch = Batch::new();
+                batch.delete_key_prefix(key);
+                store.write_batch(batch).await?;
+            }
+            let elapsed = time.elapsed();
+            assert!(store.find_keys_by_prefix(&[KEY_PREFIX]).await?.is_empty());
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "delete-prefix", "loop", statistics));
+    //
+    for &limit in &settings.in_flight_limits {
+        let statistics = measure(settings.repetitions, || {
+            let (key_values, keys) = (&key_values, keys.clone());
+            async move {
+                let store = new_filled_store::<S>(key_values).await?;
+                let time = Instant::now();
+                let mut futures = Vec::new();
+                for key in keys {
+                    let store = store.clone();
+                    futures.push(async move {
+                        let mut batch = Batch::new();
+                        batch.delete_key_prefix(key);
+                        store.write_batch(batch).await
+                    });
+                }
+                try_join_limited(futures, limit).await?;
+                let elapsed = time.elapsed();
+                assert!(store.find_keys_by_prefix(&[KEY_PREFIX]).await?.is_empty());
+                Ok::<_, anyhow::Error>(elapsed)
+            }
+        })
+        .await?;
+        report.add(parameters.record(name, "delete-prefix", &futures_strategy(limit), statistics));
+    }
+    Ok(())
+}
+
+pub async fn test_storage_contains_kernel<S>(
+    name: &str,
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore,
+{
+    let key_values = get_prefixed_key_values(parameters);
+    let store = new_filled_store::<S>(&key_values).await?;
+    // Half of the looked up keys are present, the other half is absent.
+    let mut keys = Vec::new();
+    let mut expected = Vec::new();
+    for key in key_values.keys() {
+        let mut absent_key = key.clone();
+        absent_key[0] = ABSENT_KEY_PREFIX;
+        keys.push(key.clone());
+        expected.push(true);
+        keys.push(absent_key);
+        expected.push(false);
+    }
+    //
+    let statistics = measure(settings.repetitions, || {
+        let (store, keys, expected) = (store.clone(), keys.clone(), &expected);
+        async move {
+            let time = Instant::now();
+            let results: Vec<bool> = store.contains_keys(keys).await?;
+            let elapsed = time.elapsed();
+            assert_eq!(&results, expected);
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "contains", "multi", statistics));
+    //
+    let statistics = measure(settings.repetitions, || {
+        let (store, keys, expected) = (store.clone(), keys.clone(), &expected);
+        async move {
+            let time = Instant::now();
+            let mut results = Vec::new();
+            for key in keys {
+                results.push(store.contains_key(&key).await?);
+            }
+            let elapsed = time.elapsed();
+            assert_eq!(&results, expected);
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "contains", "loop", statistics));
+    //
+    for &limit in &settings.in_flight_limits {
+        let statistics = measure(settings.repetitions, || {
+            let (store, keys, expected) = (store.clone(), keys.clone(), &expected);
+            async move {
+                let time = Instant::now();
+                let mut futures = Vec::new();
+                for key in keys {
+                    let store = store.clone();
+                    futures.push(async move { store.contains_key(&key).await });
+                }
+                let results: Vec<bool> = try_join_limited(futures, limit).await?;
+                let elapsed = time.elapsed();
+                assert_eq!(&results, expected);
+                Ok::<_, anyhow::Error>(elapsed)
+            }
+        })
+        .await?;
+        report.add(parameters.record(name, "contains", &futures_strategy(limit), statistics));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use linera_views::memory::MemoryDatabase;
+
+    use super::*;
+    use crate::{report::OutputFormat, Args};
+
+    /// Few and short keys, so that some prefixes are shared.
+    const PARAMETERS: Parameters = Parameters {
+        num_key: 200,
+        key_size: 1,
+        value_size: 4,
+    };
+
+    /// The settings of the command line `args`, with a single measured run.
+    fn settings(args: &[&str]) -> Settings {
+        let command = [
+            "benchmark",
+            "200",
+            "1",
+            "4",
+            "--warmup-runs",
+            "0",
+            "--runs",
+            "1",
+        ];
+        Settings::new(&Args::parse_from(command.iter().chain(args)))
+    }
+
+    /// The strategies of the records of `operation`, in order.
+    fn strategies(report: &Report, operation: &str) -> Vec<String> {
+        report
+            .records()
+            .iter()
+            .filter(|record| record.operation == operation)
+            .map(|record| record.strategy.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_prefixed_key_values() {
+        let key_values = get_prefixed_key_values(PARAMETERS);
+        // At most 256 distinct keys of one byte, the duplicates being merged.
+        assert!(key_values.len() < PARAMETERS.num_key);
+        for (key, value) in &key_values {
+            assert_eq!(key[0], KEY_PREFIX);
+            assert_eq!(key.len(), 2);
+            assert_eq!(value.len(), 4);
+        }
+        assert_eq!(get_prefixed_key_values(PARAMETERS), key_values);
+    }
+
+    // The kernels check the results of every strategy, so that it is enough to run them.
+
+    #[tokio::test]
+    async fn test_find_keys_kernel() -> Result<()> {
+        let settings = settings(&["--max-in-flight", "4"]);
+        let mut report = Report::new(OutputFormat::Json);
+        test_storage_find_keys_kernel::<MemoryDatabase>(
+            "memory",
+            &settings,
+            PARAMETERS,
+            &mut report,
+        )
+        .await?;
+        test_storage_find_key_values_kernel::<MemoryDatabase>(
+            "memory",
+            &settings,
+            PARAMETERS,
+            &mut report,
+        )
+        .await?;
+        let expected = ["multi", "loop", "futures", "futures-k4"];
+        assert_eq!(strategies(&report, "find-keys"), expected);
+        assert_eq!(strategies(&report, "find-key-values"), expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_prefix_kernel() -> Result<()> {
+        let settings = settings(&["--max-in-flight", "4"]);
+        let mut report = Report::new(OutputFormat::Json);
+        test_storage_delete_prefix_kernel::<MemoryDatabase>(
+            "memory",
+            &settings,
+            PARAMETERS,
+            &mut report,
+        )
+        .await?;
+        let expected = ["multi", "loop", "futures", "futures-k4"];
+        assert_eq!(strategies(&report, "delete-prefix"), expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_contains_kernel() -> Result<()> {
+        let settings = settings(&[]);
+        let mut report = Report::new(OutputFormat::Json);
+        test_storage_contains_kernel::<MemoryDatabase>(
+            "memory",
+            &settings,
+            PARAMETERS,
+            &mut report,
+        )
+        .await?;
+        assert_eq!(
+            strategies(&report, "contains"),
+            ["multi", "loop", "futures"]
+        );
+        assert!(report
+            .records()
+            .iter()
+            .all(|record| record.statistics.runs == 1));
+        Ok(())
+    }
+}
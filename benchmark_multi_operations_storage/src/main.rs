@@ -1,23 +1,223 @@
+mod batch;
+mod compare;
+mod concurrency;
+mod kernels;
+mod report;
+mod statistics;
+mod sweep;
+mod views;
+mod workload;
+
 use anyhow::Result;
-use linera_base::{
-    time::Instant,
-};
+use batch::{test_storage_batch_write_kernel, BatchScaling};
+use clap::{Parser, ValueEnum};
+use compare::{compare_with_baseline, save_baseline};
+use concurrency::{futures_strategy, try_join_limited};
+use linera_base::time::Instant;
+use linera_storage_service::client::StorageServiceDatabase;
 use linera_views::{
     batch::Batch,
     dynamo_db::DynamoDbDatabase,
+    memory::MemoryDatabase,
     rocks_db::RocksDbDatabase,
     scylla_db::ScyllaDbDatabase,
-    store::{
-        KeyValueStore,
-        ReadableKeyValueStore,
-        TestKeyValueDatabase,
-        WritableKeyValueStore,
-    },
+    store::{KeyValueStore, ReadableKeyValueStore, TestKeyValueDatabase, WritableKeyValueStore},
 };
 use rand::SeedableRng;
 
+use kernels::{
+    test_storage_contains_kernel, test_storage_delete_prefix_kernel,
+    test_storage_find_key_values_kernel, test_storage_find_keys_kernel,
+};
 use rand::Rng;
-use std::env;
+use report::{BenchmarkRecord, OutputFormat, Report};
+use statistics::{measure, Repetitions, Statistics};
+use std::path::PathBuf;
+use sweep::{Matrix, SizeList};
+use views::test_storage_views_kernel;
+use workload::{test_storage_mixed_workload_kernel, MixedWorkload};
+
+/// The storage backends that can be benchmarked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Backend {
+    Memory,
+    #[value(name = "rocksdb")]
+    RocksDb,
+    #[value(name = "scylladb")]
+    ScyllaDb,
+    #[value(name = "dynamodb")]
+    DynamoDb,
+    /// The storage service client, reached through `LINERA_STORAGE_SERVICE`.
+    StorageService,
+}
+
+impl Backend {
+    fn name(&self) -> &'static str {
+        match self {
+            Backend::Memory => "memory",
+            Backend::RocksDb => "rocksdb",
+            Backend::ScyllaDb => "scylladb",
+            Backend::DynamoDb => "dynamodb",
+            Backend::StorageService => "storage-service",
+        }
+    }
+}
+
+/// The benchmarked store operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Operation {
+    /// `put_key_value_bytes` in batches.
+    Write,
+    /// `read_multi_values_bytes` and `read_value_bytes`.
+    Read,
+    /// `find_keys_by_prefix`.
+    FindKeys,
+    /// `find_key_values_by_prefix`.
+    FindKeyValues,
+    /// `delete_key_prefix` in batches.
+    DeletePrefix,
+    /// `contains_keys` and `contains_key`.
+    Contains,
+    /// A mixed workload of reads, writes and scans, see the `--*-ratio` options.
+    Mixed,
+    /// `write_batch` with the key set split in batches of several sizes, see `--batch-sizes`.
+    BatchWrite,
+    /// Writing, reading and updating `RegisterView`, `LogView`, `MapView` and `CollectionView`,
+    /// plain and hashed.
+    Views,
+}
+
+#[derive(Parser, Debug)]
+#[command(about = "Compare multi, loop and futures access strategies on key-value stores")]
+struct Args {
+    /// Number of keys written and read, e.g. `100`. Sizes accept `k`/`m` suffixes, lists
+    /// such as `10,100,1k` and ranges such as `10..=1000*10` or `100..=500+100`;
+    /// every combination of the three lists is benchmarked.
+    #[arg(required_unless_present = "matrix")]
+    num_key: Option<SizeList>,
+
+    /// Size of each key in bytes, e.g. `10`.
+    #[arg(required_unless_present = "matrix")]
+    key_size: Option<SizeList>,
+
+    /// Size of each value in bytes, e.g. `10000`.
+    #[arg(required_unless_present = "matrix")]
+    value_size: Option<SizeList>,
+
+    /// TOML file with `num_key`, `key_size` and `value_size` arrays, replacing the positional sizes.
+    #[arg(long, conflicts_with_all = ["num_key", "key_size", "value_size"])]
+    matrix: Option<PathBuf>,
+
+    /// Backend to benchmark. Can be repeated; defaults to DynamoDB, ScyllaDB and RocksDB.
+    #[arg(long = "backend", value_enum, default_values = ["dynamodb", "scylladb", "rocksdb"])]
+    backends: Vec<Backend>,
+
+    /// Operation to benchmark. Can be repeated; defaults to write and read.
+    #[arg(long = "operation", value_enum, default_values = ["write", "read"])]
+    operations: Vec<Operation>,
+
+    /// Number of discarded runs of each strategy before measuring.
+    #[arg(long, default_value_t = 1)]
+    warmup_runs: usize,
+
+    /// Number of measured runs of each strategy.
+    #[arg(long, default_value_t = 10, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    runs: usize,
+
+    /// Format of the results: text lines, or one JSON/CSV record per backend, operation and strategy.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// File receiving the JSON/CSV records, or the table of the records with the text format.
+    /// The JSON/CSV records are printed on stdout if not given.
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Also run the futures strategies with at most this many requests in flight.
+    /// Accepts a list to sweep over, e.g. `1,4,16,64`.
+    #[arg(long, value_parser = SizeList::parse_positive)]
+    max_in_flight: Option<SizeList>,
+
+    /// Saves the records of the run as a JSON baseline for a later `--compare`.
+    #[arg(long)]
+    save_baseline: Option<PathBuf>,
+
+    /// Compares the medians with the ones of a baseline saved with `--save-baseline`,
+    /// exiting with an error if some of them regressed.
+    #[arg(long)]
+    compare: Option<PathBuf>,
+
+    /// Increase of a median, in percent, above which `--compare` reports a regression.
+    #[arg(long, default_value_t = 10.0)]
+    regression_threshold: f64,
+
+    #[command(flatten)]
+    workload: MixedWorkload,
+
+    #[command(flatten)]
+    batch_scaling: BatchScaling,
+}
+
+/// What is run for every backend and size.
+struct Settings {
+    operations: Vec<Operation>,
+    repetitions: Repetitions,
+    workload: MixedWorkload,
+    batch_scaling: BatchScaling,
+    /// The in-flight limits of the futures strategies, `None` meaning all requests at once.
+    in_flight_limits: Vec<Option<usize>>,
+}
+
+impl Settings {
+    fn new(args: &Args) -> Self {
+        Settings {
+            operations: args.operations.clone(),
+            repetitions: Repetitions {
+                warmup_runs: args.warmup_runs,
+                runs: args.runs,
+            },
+            workload: args.workload.clone(),
+            batch_scaling: args.batch_scaling.clone(),
+            in_flight_limits: std::iter::once(None)
+                .chain(
+                    args.max_in_flight
+                        .iter()
+                        .flat_map(|limits| limits.0.iter().copied())
+                        .map(Some),
+                )
+                .collect(),
+        }
+    }
+}
+
+/// The sizes of the data set used by the kernels.
+#[derive(Clone, Copy, Debug)]
+struct Parameters {
+    num_key: usize,
+    key_size: usize,
+    value_size: usize,
+}
+
+impl Parameters {
+    fn record(
+        &self,
+        backend: &str,
+        operation: &str,
+        strategy: &str,
+        statistics: Statistics,
+    ) -> BenchmarkRecord {
+        BenchmarkRecord {
+            backend: backend.to_string(),
+            operation: operation.to_string(),
+            strategy: strategy.to_string(),
+            num_key: self.num_key,
+            key_size: self.key_size,
+            value_size: self.value_size,
+            statistics,
+            throughput: None,
+        }
+    }
+}
 
 fn get_key(rng: &mut impl Rng, len: usize) -> Vec<u8> {
     let mut v = Vec::new();
@@ -28,173 +228,299 @@ fn get_key(rng: &mut impl Rng, len: usize) -> Vec<u8> {
     v
 }
 
-async fn test_storage_multi_write_kernel<S>(num_key: usize, key_size: usize, value_size: usize) -> Result<()>
+async fn test_storage_multi_write_kernel<S>(
+    name: &str,
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()>
 where
     S: TestKeyValueDatabase,
     S::Store: Clone + KeyValueStore,
 {
-    let big_name = S::get_name();
-    let l_str = big_name
-        .split(' ')
-        .map(|x| x.to_string())
-        .collect::<Vec<_>>();
-    let name = l_str[l_str.len() - 2].clone();
     let mut rng = rand::rngs::StdRng::seed_from_u64(134 as u64);
-    let mut key_values1 = Vec::new();
-    let mut key_values2 = Vec::new();
-    let mut key_values3 = Vec::new();
-    for _ in 0..num_key {
-        let key = get_key(&mut rng, key_size);
-        let value = get_key(&mut rng, value_size);
-        key_values1.push((key.clone(), value.clone()));
-        key_values2.push((key.clone(), value.clone()));
-        key_values3.push((key, value));
+    let mut key_values = Vec::new();
+    for _ in 0..parameters.num_key {
+        let key = get_key(&mut rng, parameters.key_size);
+        let value = get_key(&mut rng, parameters.value_size);
+        key_values.push((key, value));
     }
 
     //
-    // Mukti write
+    // Multi write
     //
-    let store1 = S::new_test_store().await?;
-    let time = Instant::now();
-    let mut batch = Batch::new();
-    for (key, value) in key_values1 {
-        batch.put_key_value_bytes(key, value);
-    }
-    store1.write_batch(batch).await?;
-    println!("Runtime {name} for   multi write: {} micros", time.elapsed().as_micros() as f64);
+    let statistics = measure(settings.repetitions, || {
+        let key_values = key_values.clone();
+        async move {
+            let store = S::new_test_store().await?;
+            let time = Instant::now();
+            let mut batch = Batch::new();
+            for (key, value) in key_values {
+                batch.put_key_value_bytes(key, value);
+            }
+            store.write_batch(batch).await?;
+            Ok::<_, anyhow::Error>(time.elapsed())
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "write", "multi", statistics));
 
     //
     // Direct write
     //
-    let store2 = S::new_test_store().await?;
-    let time = Instant::now();
-    for (key, value) in key_values2 {
-        let mut batch = Batch::new();
-        batch.put_key_value_bytes(key, value);
-        store2.write_batch(batch).await?;
-    }
-    println!("Runtime {name} for    loop write: {} micros", time.elapsed().as_micros() as f64);
+    let statistics = measure(settings.repetitions, || {
+        let key_values = key_values.clone();
+        async move {
+            let store = S::new_test_store().await?;
+            let time = Instant::now();
+            for (key, value) in key_values {
+                let mut batch = Batch::new();
+                batch.put_key_value_bytes(key, value);
+                store.write_batch(batch).await?;
+            }
+            Ok::<_, anyhow::Error>(time.elapsed())
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "write", "loop", statistics));
 
     //
     // Futures write
     //
-    let store3 = S::new_test_store().await?;
-    let time = Instant::now();
-    let mut futures = Vec::new();
-    for (key, value) in key_values3 {
-        let store = store3.clone();
-        futures.push(async move {
-            let mut batch = Batch::new();
-            batch.put_key_value_bytes(key, value);
-            store.write_batch(batch).await
-        });
+    for &limit in &settings.in_flight_limits {
+        let statistics = measure(settings.repetitions, || {
+            let key_values = key_values.clone();
+            async move {
+                let store = S::new_test_store().await?;
+                let time = Instant::now();
+                let mut futures = Vec::new();
+                for (key, value) in key_values {
+                    let store = store.clone();
+                    futures.push(async move {
+                        let mut batch = Batch::new();
+                        batch.put_key_value_bytes(key, value);
+                        store.write_batch(batch).await
+                    });
+                }
+                try_join_limited(futures, limit).await?;
+                Ok::<_, anyhow::Error>(time.elapsed())
+            }
+        })
+        .await?;
+        report.add(parameters.record(name, "write", &futures_strategy(limit), statistics));
     }
-    futures::future::try_join_all(futures).await?;
-    println!("Runtime {name} for futures write: {} micros", time.elapsed().as_micros() as f64);
     Ok(())
 }
 
-
-
-
-async fn test_storage_multi_read_kernel<S>(num_key: usize, key_size: usize, value_size: usize) -> Result<()>
+async fn test_storage_multi_read_kernel<S>(
+    name: &str,
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()>
 where
     S: TestKeyValueDatabase,
     S::Store: Clone + KeyValueStore,
 {
-    let big_name = S::get_name();
-    println!("big_name={big_name}");
-    let l_str = big_name
-        .split(' ')
-        .map(|x| x.to_string())
-        .collect::<Vec<_>>();
-    let name = l_str[l_str.len() - 2].clone();
+    report.log(&format!("big_name={}", S::get_name()));
     let store = S::new_test_store().await?;
     let mut batch = Batch::new();
     let mut rng = rand::rngs::StdRng::seed_from_u64(134 as u64);
-    let mut keys1 = Vec::new();
-    let mut keys2 = Vec::new();
-    let mut keys3 = Vec::new();
+    let mut keys = Vec::new();
     let mut read_values = Vec::new();
-    for _ in 0..num_key {
-        let key = get_key(&mut rng, key_size);
-        let value = get_key(&mut rng, value_size);
-        keys1.push(key.clone());
-        keys2.push(key.clone());
-        keys3.push(key.clone());
+    for _ in 0..parameters.num_key {
+        let key = get_key(&mut rng, parameters.key_size);
+        let value = get_key(&mut rng, parameters.value_size);
+        keys.push(key.clone());
         read_values.push(Some(value.clone()));
         batch.put_key_value_bytes(key, value);
     }
     store.write_batch(batch).await?;
     //
-    let time = Instant::now();
-    let values: Vec<Option<Vec<u8>>> = store.read_multi_values_bytes(keys1).await?;
-    println!("Runtime {name} for   multi read: {} micros", time.elapsed().as_micros() as f64);
-    assert_eq!(values, read_values);
+    let statistics = measure(settings.repetitions, || {
+        let (store, keys, read_values) = (store.clone(), keys.clone(), &read_values);
+        async move {
+            let time = Instant::now();
+            let values: Vec<Option<Vec<u8>>> = store.read_multi_values_bytes(keys).await?;
+            let elapsed = time.elapsed();
+            assert_eq!(&values, read_values);
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "read", "multi", statistics));
     //
-    let time = Instant::now();
-    let mut values: Vec<Option<Vec<u8>>> = Vec::new();
-    for key in keys2 {
-        values.push(store.read_value_bytes(&key).await?);
-    }
-    println!("Runtime {name} for    loop read: {} micros", time.elapsed().as_micros() as f64);
-    assert_eq!(values, read_values);
+    let statistics = measure(settings.repetitions, || {
+        let (store, keys, read_values) = (store.clone(), keys.clone(), &read_values);
+        async move {
+            let time = Instant::now();
+            let mut values: Vec<Option<Vec<u8>>> = Vec::new();
+            for key in keys {
+                values.push(store.read_value_bytes(&key).await?);
+            }
+            let elapsed = time.elapsed();
+            assert_eq!(&values, read_values);
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    report.add(parameters.record(name, "read", "loop", statistics));
     //
-    let time = Instant::now();
-    let mut futures = Vec::new();
-    for key in keys3 {
-        let store = store.clone();
-        futures.push(async move { store.read_value_bytes(&key).await });
-    }
-    let values: Vec<Option<Vec<u8>>> = futures::future::try_join_all(futures).await?;
-    println!("Runtime {name} for futures read: {} micros", time.elapsed().as_micros() as f64);
-    assert_eq!(values, read_values);
+    for &limit in &settings.in_flight_limits {
+        let statistics = measure(settings.repetitions, || {
+            let (store, keys, read_values) = (store.clone(), keys.clone(), &read_values);
+            async move {
+                let time = Instant::now();
+                let mut futures = Vec::new();
+                for key in keys {
+                    let store = store.clone();
+                    futures.push(async move { store.read_value_bytes(&key).await });
+                }
+                let values: Vec<Option<Vec<u8>>> = try_join_limited(futures, limit).await?;
+                let elapsed = time.elapsed();
+                assert_eq!(&values, read_values);
+                Ok::<_, anyhow::Error>(elapsed)
+            }
+        })
+        .await?;
+        report.add(parameters.record(name, "read", &futures_strategy(limit), statistics));
+    }
     //
     Ok(())
 }
 
-async fn test_storage_multi_kernel<S>(num_key: usize, key_size: usize, value_size: usize) -> Result<()>
+async fn test_storage_multi_kernel<S>(
+    name: &str,
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()>
 where
     S: TestKeyValueDatabase,
-    S::Store: Clone + KeyValueStore,
+    S::Store: Clone + KeyValueStore + 'static,
 {
-    println!("------------------------------------");
-    test_storage_multi_write_kernel::<S>(num_key, key_size, value_size).await?;
-    test_storage_multi_read_kernel::<S>(num_key, key_size, value_size).await?;
+    report.log("------------------------------------");
+    for operation in &settings.operations {
+        match operation {
+            Operation::Write => {
+                test_storage_multi_write_kernel::<S>(name, settings, parameters, report).await?
+            }
+            Operation::Read => {
+                test_storage_multi_read_kernel::<S>(name, settings, parameters, report).await?
+            }
+            Operation::FindKeys => {
+                test_storage_find_keys_kernel::<S>(name, settings, parameters, report).await?
+            }
+            Operation::FindKeyValues => {
+                test_storage_find_key_values_kernel::<S>(name, settings, parameters, report).await?
+            }
+            Operation::DeletePrefix => {
+                test_storage_delete_prefix_kernel::<S>(name, settings, parameters, report).await?
+            }
+            Operation::Contains => {
+                test_storage_contains_kernel::<S>(name, settings, parameters, report).await?
+            }
+            Operation::Mixed => {
+                test_storage_mixed_workload_kernel::<S>(name, settings, parameters, report).await?
+            }
+            Operation::BatchWrite => {
+                test_storage_batch_write_kernel::<S>(name, settings, parameters, report).await?
+            }
+            Operation::Views => {
+                test_storage_views_kernel::<S>(name, settings, parameters, report).await?
+            }
+        }
+    }
     Ok(())
 }
 
-
-async fn test_various_storage(num_key: usize, key_size: usize, value_size: usize) -> Result<()> {
-    test_storage_multi_kernel::<DynamoDbDatabase>(num_key, key_size, value_size).await?;
-    test_storage_multi_kernel::<ScyllaDbDatabase>(num_key, key_size, value_size).await?;
-    test_storage_multi_kernel::<RocksDbDatabase>(num_key, key_size, value_size).await?;
+async fn test_various_storage(
+    backends: &[Backend],
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()> {
+    for backend in backends {
+        let name = backend.name();
+        match backend {
+            Backend::Memory => {
+                test_storage_multi_kernel::<MemoryDatabase>(name, settings, parameters, report)
+                    .await?
+            }
+            Backend::RocksDb => {
+                test_storage_multi_kernel::<RocksDbDatabase>(name, settings, parameters, report)
+                    .await?
+            }
+            Backend::ScyllaDb => {
+                test_storage_multi_kernel::<ScyllaDbDatabase>(name, settings, parameters, report)
+                    .await?
+            }
+            Backend::DynamoDb => {
+                test_storage_multi_kernel::<DynamoDbDatabase>(name, settings, parameters, report)
+                    .await?
+            }
+            Backend::StorageService => {
+                test_storage_multi_kernel::<StorageServiceDatabase>(
+                    name, settings, parameters, report,
+                )
+                .await?
+            }
+        }
+    }
     Ok(())
 }
 
-
-
-
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
+    let args = Args::parse();
 
-    if args.len() < 4 {
-        eprintln!("Error: argument specified");
-        eprintln!("Usage: {} [num_key] [key_size] [value_size]", args[0]);
-        eprintln!("e.g. num_key = 100, key_size = 10, value_size = 10000");
+    let settings = Settings::new(&args);
+    let matrix = match (&args.matrix, args.num_key, args.key_size, args.value_size) {
+        (Some(path), _, _, _) => Matrix::from_toml_file(path)?,
+        (None, Some(num_key), Some(key_size), Some(value_size)) => Matrix {
+            num_key: num_key.0,
+            key_size: key_size.0,
+            value_size: value_size.0,
+        },
+        _ => unreachable!("clap requires the sizes when no matrix is given"),
+    };
+    let combinations = matrix.combinations();
+    let mut report = Report::new(args.format);
+    for parameters in &combinations {
+        report.log(&format!(
+            "==== num_key={} key_size={} value_size={} ====",
+            parameters.num_key, parameters.key_size, parameters.value_size
+        ));
+        test_various_storage(&args.backends, &settings, *parameters, &mut report).await?;
+    }
+    if combinations.len() > 1 {
+        report.print_table();
+    }
+    report.write(args.output.as_deref())?;
+    if let Some(path) = &args.save_baseline {
+        save_baseline(path, report.records())?;
+    }
+    let mut success = true;
+    if let Some(path) = &args.compare {
+        let num_regressions = compare_with_baseline(path, args.regression_threshold, &report)?;
+        if num_regressions > 0 {
+            eprintln!(
+                "Error: {num_regressions} regression(s) with respect to {}",
+                path.display()
+            );
+            success = false;
+        }
+    }
+    if !report.failures().is_empty() {
+        eprintln!("Error: {} measure(s) failed:", report.failures().len());
+        for failure in report.failures() {
+            eprintln!("    {failure}");
+        }
+        success = false;
+    }
+    if !success {
         std::process::exit(1);
     }
 
-    let num_key = &args[1];
-    let key_size = &args[2];
-    let value_size = &args[3];
-
-    let num_key = num_key.parse::<usize>()?;
-    let key_size = key_size.parse::<usize>()?;
-    let value_size = value_size.parse::<usize>()?;
-
-    test_various_storage(num_key, key_size, value_size).await?;
-
     Ok(())
 }
@@ -0,0 +1,213 @@
+use std::{fs::File, io::Write, path::Path};
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::statistics::Statistics;
+
+/// How the collected records are emitted at the end of the run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable `Runtime ...` lines, printed as the benchmark progresses.
+    Text,
+    Json,
+    Csv,
+}
+
+/// One measured strategy for a given backend, operation and workload size.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BenchmarkRecord {
+    pub backend: String,
+    pub operation: String,
+    pub strategy: String,
+    pub num_key: usize,
+    pub key_size: usize,
+    pub value_size: usize,
+    #[serde(flatten)]
+    pub statistics: Statistics,
+    /// Operations per second, for the workloads that measure it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput: Option<f64>,
+}
+
+const CSV_HEADER: &str =
+    "backend,operation,strategy,num_key,key_size,value_size,runs,min,median,p95,p99,max,mean,std_dev,throughput";
+
+impl BenchmarkRecord {
+    /// Whether both records measure the same backend, operation and sizes.
+    fn same_case(&self, other: &BenchmarkRecord) -> bool {
+        self.backend == other.backend
+            && self.operation == other.operation
+            && self.num_key == other.num_key
+            && self.key_size == other.key_size
+            && self.value_size == other.value_size
+    }
+
+    /// Whether both records measure the same strategy for the same backend, operation and sizes.
+    pub fn same_measure(&self, other: &BenchmarkRecord) -> bool {
+        self.same_case(other) && self.strategy == other.strategy
+    }
+
+    pub fn description(&self) -> String {
+        format!(
+            "{} {} {} (num_key={} key_size={} value_size={})",
+            self.backend,
+            self.operation,
+            self.strategy,
+            self.num_key,
+            self.key_size,
+            self.value_size
+        )
+    }
+
+    fn to_csv_line(&self) -> String {
+        let s = &self.statistics;
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.backend,
+            self.operation,
+            self.strategy,
+            self.num_key,
+            self.key_size,
+            self.value_size,
+            s.runs,
+            s.min,
+            s.median,
+            s.p95,
+            s.p99,
+            s.max,
+            s.mean,
+            s.std_dev,
+            self.throughput
+                .map(|throughput| throughput.to_string())
+                .unwrap_or_default()
+        )
+    }
+}
+
+/// Collects the records of a run and prints progress according to the output format.
+pub struct Report {
+    format: OutputFormat,
+    records: Vec<BenchmarkRecord>,
+    /// The measures that could not be done, e.g. a batch size refused by a backend.
+    failures: Vec<String>,
+}
+
+impl Report {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            records: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    /// Prints an informational line. It goes to stderr for the structured
+    /// formats, so that stdout only contains the records.
+    pub fn log(&self, line: &str) {
+        match self.format {
+            OutputFormat::Text => println!("{line}"),
+            OutputFormat::Json | OutputFormat::Csv => eprintln!("{line}"),
+        }
+    }
+
+    pub fn records(&self) -> &[BenchmarkRecord] {
+        &self.records
+    }
+
+    pub fn failures(&self) -> &[String] {
+        &self.failures
+    }
+
+    /// Records a measure that failed without aborting the run.
+    pub fn add_failure(&mut self, failure: String) {
+        self.log(&format!("FAILURE {failure}"));
+        self.failures.push(failure);
+    }
+
+    pub fn add(&mut self, record: BenchmarkRecord) {
+        self.log(&format!(
+            "Runtime {} for {:>7} {}: {}",
+            record.backend, record.strategy, record.operation, record.statistics
+        ));
+        self.records.push(record);
+    }
+
+    /// Prints all the records in one table.
+    pub fn print_table(&self) {
+        for line in self.table_lines() {
+            self.log(&line);
+        }
+    }
+
+    /// The lines of the table of all the records. The `ratio` column is the median
+    /// relative to the fastest strategy for the same backend, operation and sizes.
+    fn table_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{:<16} {:<16} {:<14} {:>8} {:>8} {:>10} {:>12} {:>12} {:>12} {:>7}",
+            "backend",
+            "operation",
+            "strategy",
+            "num_key",
+            "key_size",
+            "value_size",
+            "median",
+            "p95",
+            "std_dev",
+            "ratio"
+        )];
+        for record in &self.records {
+            let best = self
+                .records
+                .iter()
+                .filter(|other| other.same_case(record))
+                .map(|other| other.statistics.median)
+                .fold(f64::INFINITY, f64::min);
+            let s = &record.statistics;
+            lines.push(format!(
+                "{:<16} {:<16} {:<14} {:>8} {:>8} {:>10} {:>12.0} {:>12.0} {:>12.1} {:>7.2}",
+                record.backend,
+                record.operation,
+                record.strategy,
+                record.num_key,
+                record.key_size,
+                record.value_size,
+                s.median,
+                s.p95,
+                s.std_dev,
+                s.median / best
+            ));
+        }
+        lines.extend(
+            self.failures
+                .iter()
+                .map(|failure| format!("FAILURE {failure}")),
+        );
+        lines
+    }
+
+    /// Writes the records in the structured format, to `output` if given and to stdout
+    /// otherwise. With the text format, the lines are already printed, so only the
+    /// table of the records is written, and only to `output`.
+    pub fn write(&self, output: Option<&Path>) -> Result<()> {
+        let content = match (self.format, output) {
+            (OutputFormat::Text, None) => return Ok(()),
+            (OutputFormat::Text, Some(_)) => self.table_lines().join("\n"),
+            (OutputFormat::Json, _) => serde_json::to_string_pretty(&self.records)?,
+            (OutputFormat::Csv, _) => {
+                let mut lines = vec![CSV_HEADER.to_string()];
+                lines.extend(self.records.iter().map(BenchmarkRecord::to_csv_line));
+                lines.join("\n")
+            }
+        };
+        match output {
+            Some(path) => {
+                let mut file = File::create(path)?;
+                writeln!(file, "{content}")?;
+            }
+            None => println!("{content}"),
+        }
+        Ok(())
+    }
+}
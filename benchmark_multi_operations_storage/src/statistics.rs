@@ -0,0 +1,101 @@
+use std::{fmt, future::Future};
+
+use anyhow::Result;
+use benchmark_statistics::percentile;
+use linera_base::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Number of discarded warm-up runs and of measured runs for each strategy.
+#[derive(Clone, Copy, Debug)]
+pub struct Repetitions {
+    pub warmup_runs: usize,
+    pub runs: usize,
+}
+
+/// Summary of the measured runtimes of one strategy, in microseconds.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Statistics {
+    pub runs: usize,
+    pub min: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl Statistics {
+    pub fn new(samples: &[f64]) -> Self {
+        assert!(!samples.is_empty(), "at least one measured run is needed");
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let len = sorted.len();
+        let median = if len % 2 == 0 {
+            (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0
+        } else {
+            sorted[len / 2]
+        };
+        let mean = sorted.iter().sum::<f64>() / len as f64;
+        let variance = sorted.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / len as f64;
+        Self {
+            runs: len,
+            min: sorted[0],
+            median,
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+            max: sorted[len - 1],
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min={:.0} median={:.0} p95={:.0} p99={:.0} max={:.0} std_dev={:.1} micros (runs={})",
+            self.min, self.median, self.p95, self.p99, self.max, self.std_dev, self.runs
+        )
+    }
+}
+
+/// Calls `run` for the warm-up runs and then for the measured runs.
+/// Each call returns the duration of its timed section, so that the setup
+/// (e.g. creating a fresh store) is not accounted for.
+pub async fn measure<F, Fut>(repetitions: Repetitions, mut run: F) -> Result<Statistics>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Duration>>,
+{
+    for _ in 0..repetitions.warmup_runs {
+        run().await?;
+    }
+    let mut samples = Vec::new();
+    for _ in 0..repetitions.runs {
+        samples.push(run().await?.as_micros() as f64);
+    }
+    Ok(Statistics::new(&samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statistics() {
+        let statistics = Statistics::new(&[4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(statistics.runs, 4);
+        assert_eq!(statistics.min, 1.0);
+        assert_eq!(statistics.median, 2.5);
+        assert_eq!(statistics.max, 4.0);
+        assert_eq!(statistics.p99, 4.0);
+        assert_eq!(statistics.mean, 2.5);
+        assert_eq!(statistics.std_dev, 1.25_f64.sqrt());
+
+        let statistics = Statistics::new(&[5.0, 1.0, 3.0]);
+        assert_eq!(statistics.median, 3.0);
+        assert_eq!(Statistics::new(&[7.0]).std_dev, 0.0);
+    }
+}
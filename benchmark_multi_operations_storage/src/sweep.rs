@@ -0,0 +1,211 @@
+use std::{path::Path, str::FromStr};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use serde::Deserialize;
+
+use crate::Parameters;
+
+/// Parses a size with an optional `k` (thousand) or `m` (million) suffix, e.g. `100k`.
+fn parse_size(input: &str) -> Result<usize> {
+    let input = input.trim();
+    let (digits, factor) = match input.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&input[..i], 1_000),
+        Some((i, 'm' | 'M')) => (&input[..i], 1_000_000),
+        _ => (input, 1),
+    };
+    let value = digits
+        .parse::<usize>()
+        .with_context(|| format!("invalid size {input}"))?;
+    value
+        .checked_mul(factor)
+        .with_context(|| format!("size {input} is too large"))
+}
+
+/// Parses `start..=end*factor` (geometric) or `start..=end+step` (arithmetic).
+fn parse_range(input: &str) -> Result<Vec<usize>> {
+    let error =
+        || anyhow!("invalid range {input}, expected start..=end*factor or start..=end+step");
+    let (start, rest) = input.split_once("..=").ok_or_else(error)?;
+    let (end, geometric, step) = if let Some((end, factor)) = rest.split_once('*') {
+        (end, true, parse_size(factor)?)
+    } else if let Some((end, step)) = rest.split_once('+') {
+        (end, false, parse_size(step)?)
+    } else {
+        return Err(error());
+    };
+    let (start, end) = (parse_size(start)?, parse_size(end)?);
+    if start == 0 || start > end || step < if geometric { 2 } else { 1 } {
+        return Err(error());
+    }
+    let mut values = Vec::new();
+    let mut value = Some(start);
+    // A next value that overflows is past `end`, so it just ends the range.
+    while let Some(current) = value.filter(|value| *value <= end) {
+        values.push(current);
+        value = if geometric {
+            current.checked_mul(step)
+        } else {
+            current.checked_add(step)
+        };
+    }
+    Ok(values)
+}
+
+/// A list of sizes given on the command line, e.g. `100`, `10,1k,100k` or `10..=1000*10`.
+#[derive(Clone, Debug)]
+pub struct SizeList(pub Vec<usize>);
+
+impl FromStr for SizeList {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut values = Vec::new();
+        for item in input.split(',') {
+            if item.contains("..") {
+                values.extend(parse_range(item)?);
+            } else {
+                values.push(parse_size(item)?);
+            }
+        }
+        Ok(SizeList(values))
+    }
+}
+
+impl SizeList {
+    /// Parses a list of sizes that must all be positive, e.g. batch sizes.
+    pub fn parse_positive(input: &str) -> Result<Self> {
+        let sizes = input.parse::<SizeList>()?;
+        ensure!(
+            sizes.0.iter().all(|size| *size > 0),
+            "invalid list {input}, every size must be at least 1"
+        );
+        Ok(sizes)
+    }
+}
+
+/// An entry of the TOML matrix: either an integer or a string such as `"1k"` or `"10..=1000*10"`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MatrixEntry {
+    Size(usize),
+    Spec(String),
+}
+
+fn expand_entries(entries: Vec<MatrixEntry>) -> Result<Vec<usize>> {
+    let mut values = Vec::new();
+    for entry in entries {
+        match entry {
+            MatrixEntry::Size(size) => values.push(size),
+            MatrixEntry::Spec(spec) => values.extend(spec.parse::<SizeList>()?.0),
+        }
+    }
+    Ok(values)
+}
+
+#[derive(Deserialize)]
+struct MatrixFile {
+    num_key: Vec<MatrixEntry>,
+    key_size: Vec<MatrixEntry>,
+    value_size: Vec<MatrixEntry>,
+}
+
+/// The sizes to benchmark. Every combination is run.
+#[derive(Clone, Debug)]
+pub struct Matrix {
+    pub num_key: Vec<usize>,
+    pub key_size: Vec<usize>,
+    pub value_size: Vec<usize>,
+}
+
+impl Matrix {
+    /// Reads a matrix like
+    /// ```toml
+    /// num_key = [10, 100, 1000]
+    /// key_size = [10]
+    /// value_size = [10, "1k", "100k"]
+    /// ```
+    pub fn from_toml_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read the matrix file {}", path.display()))?;
+        let file: MatrixFile = toml::from_str(&content)
+            .with_context(|| format!("failed to parse the matrix file {}", path.display()))?;
+        Ok(Matrix {
+            num_key: expand_entries(file.num_key)?,
+            key_size: expand_entries(file.key_size)?,
+            value_size: expand_entries(file.value_size)?,
+        })
+    }
+
+    pub fn combinations(&self) -> Vec<Parameters> {
+        let mut combinations = Vec::new();
+        for &num_key in &self.num_key {
+            for &key_size in &self.key_size {
+                for &value_size in &self.value_size {
+                    combinations.push(Parameters {
+                        num_key,
+                        key_size,
+                        value_size,
+                    });
+                }
+            }
+        }
+        combinations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("100").unwrap(), 100);
+        assert_eq!(parse_size(" 10k ").unwrap(), 10_000);
+        assert_eq!(parse_size("2M").unwrap(), 2_000_000);
+        assert!(parse_size("").is_err());
+        assert!(parse_size("k").is_err());
+        assert!(parse_size("10g").is_err());
+        assert!(parse_size(&format!("{}m", usize::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("10..=1000*10").unwrap(), [10, 100, 1000]);
+        assert_eq!(parse_range("1..=10*3").unwrap(), [1, 3, 9]);
+        assert_eq!(
+            parse_range("100..=500+100").unwrap(),
+            [100, 200, 300, 400, 500]
+        );
+        assert_eq!(parse_range("1k..=3k+1k").unwrap(), [1000, 2000, 3000]);
+        assert!(parse_range("1..=10").is_err());
+        assert!(parse_range("0..=10*2").is_err());
+        assert!(parse_range("10..=1*2").is_err());
+        assert!(parse_range("1..=10*1").is_err());
+        assert!(parse_range("1..=10+0").is_err());
+        assert!(parse_range(&format!("1..={}0*2", usize::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_parse_range_up_to_the_largest_size() {
+        let max = usize::MAX;
+        let powers = parse_range(&format!("1..={max}*2")).unwrap();
+        assert_eq!(powers.len(), usize::BITS as usize);
+        assert_eq!(powers.last(), Some(&(1 << (usize::BITS - 1))));
+        assert_eq!(parse_range(&format!("1..={max}+{max}")).unwrap(), [1]);
+        assert_eq!(parse_range(&format!("{max}..={max}+1")).unwrap(), [max]);
+    }
+
+    #[test]
+    fn test_size_list() {
+        let sizes = "10,1k,1..=100*10".parse::<SizeList>().unwrap();
+        assert_eq!(sizes.0, [10, 1000, 1, 10, 100]);
+        assert!("10,,20".parse::<SizeList>().is_err());
+    }
+
+    #[test]
+    fn test_positive_size_list() {
+        assert_eq!(SizeList::parse_positive("1,4,16").unwrap().0, [1, 4, 16]);
+        assert!(SizeList::parse_positive("0").is_err());
+        assert!(SizeList::parse_positive("4,0,16").is_err());
+    }
+}
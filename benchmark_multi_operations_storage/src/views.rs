@@ -0,0 +1,362 @@
+//! Benchmarks of the views used by the contracts (`RegisterView`, `LogView`,
+//! `MapView` and `CollectionView`) on top of the same stores as the raw kernels.
+//! For each view, three phases are timed:
+//! * `write`: filling an empty view with the data set and saving it,
+//! * `read`: loading the saved view and reading back every entry,
+//! * `update`: loading the saved view, replacing every value and saving it.
+//!
+//! Each view is also benchmarked in its hashed version (e.g. `HashedMapView`), whose
+//! hash is computed before saving, as for the chain state after a block, and stored
+//! with it. The difference with the plain view is the overhead of hashing.
+
+use anyhow::{ensure, Result};
+use linera_base::time::Instant;
+use linera_views::{
+    collection_view::{CollectionView, HashedCollectionView},
+    context::ViewContext,
+    log_view::{HashedLogView, LogView},
+    map_view::{HashedMapView, MapView},
+    register_view::{HashedRegisterView, RegisterView},
+    store::{KeyValueStore, TestKeyValueDatabase},
+    views::{HashableView, RootView, View},
+};
+use rand::SeedableRng;
+
+use crate::{get_key, kernels::get_key_values, measure, Parameters, Report, Settings};
+
+/// A root view with one field per benchmarked view. Each benchmark only uses one field.
+#[derive(RootView)]
+struct BenchmarkState<C> {
+    /// The whole data set in one value, as a contract keeping a struct in a register.
+    register: RegisterView<C, Vec<Vec<u8>>>,
+    log: LogView<C, Vec<u8>>,
+    map: MapView<C, Vec<u8>, Vec<u8>>,
+    collection: CollectionView<C, Vec<u8>, RegisterView<C, Vec<u8>>>,
+    hashed_register: HashedRegisterView<C, Vec<Vec<u8>>>,
+    hashed_log: HashedLogView<C, Vec<u8>>,
+    hashed_map: HashedMapView<C, Vec<u8>, Vec<u8>>,
+    hashed_collection: HashedCollectionView<C, Vec<u8>, RegisterView<C, Vec<u8>>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum ViewKind {
+    Register,
+    Log,
+    Map,
+    Collection,
+}
+
+impl ViewKind {
+    const ALL: [ViewKind; 4] = [
+        ViewKind::Register,
+        ViewKind::Log,
+        ViewKind::Map,
+        ViewKind::Collection,
+    ];
+
+    fn name(&self, hashed: bool) -> String {
+        let name = match self {
+            ViewKind::Register => "register-view",
+            ViewKind::Log => "log-view",
+            ViewKind::Map => "map-view",
+            ViewKind::Collection => "collection-view",
+        };
+        if hashed {
+            format!("hashed-{name}")
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+async fn load_state<S>(store: &S::Store) -> Result<BenchmarkState<ViewContext<(), S::Store>>>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore + 'static,
+{
+    let context = ViewContext::create_root_context(store.clone(), ()).await?;
+    Ok(BenchmarkState::load(context).await?)
+}
+
+/// Sets the values of the view, the keys being only used by the map and the collection.
+/// The log is appended to, so it is cleared first.
+async fn set_values<S>(
+    kind: ViewKind,
+    hashed: bool,
+    state: &mut BenchmarkState<ViewContext<(), S::Store>>,
+    keys: &[Vec<u8>],
+    values: &[Vec<u8>],
+) -> Result<()>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore + 'static,
+{
+    match kind {
+        ViewKind::Register => {
+            let register = if hashed {
+                &mut *state.hashed_register
+            } else {
+                &mut state.register
+            };
+            register.set(values.to_vec());
+        }
+        ViewKind::Log => {
+            let log = if hashed {
+                &mut *state.hashed_log
+            } else {
+                &mut state.log
+            };
+            log.clear();
+            for value in values {
+                log.push(value.clone());
+            }
+        }
+        ViewKind::Map => {
+            let map = if hashed {
+                &mut *state.hashed_map
+            } else {
+                &mut state.map
+            };
+            for (key, value) in keys.iter().zip(values) {
+                map.insert(key, value.clone())?;
+            }
+        }
+        ViewKind::Collection => {
+            let collection = if hashed {
+                &mut *state.hashed_collection
+            } else {
+                &mut state.collection
+            };
+            for (key, value) in keys.iter().zip(values) {
+                collection.load_entry_mut(key).await?.set(value.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads back every entry of the view and checks it against the expected values.
+async fn check_values<S>(
+    kind: ViewKind,
+    hashed: bool,
+    state: &BenchmarkState<ViewContext<(), S::Store>>,
+    keys: &[Vec<u8>],
+    values: &[Vec<u8>],
+) -> Result<()>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore + 'static,
+{
+    match kind {
+        ViewKind::Register => {
+            let register = if hashed {
+                &*state.hashed_register
+            } else {
+                &state.register
+            };
+            ensure!(register.get() == values, "wrong register value");
+        }
+        ViewKind::Log => {
+            let log = if hashed {
+                &*state.hashed_log
+            } else {
+                &state.log
+            };
+            ensure!(
+                log.read(0..log.count()).await? == values,
+                "wrong log values"
+            );
+        }
+        ViewKind::Map => {
+            let map = if hashed {
+                &*state.hashed_map
+            } else {
+                &state.map
+            };
+            for (key, value) in keys.iter().zip(values) {
+                ensure!(
+                    map.get(key).await?.as_ref() == Some(value),
+                    "wrong map value"
+                );
+            }
+        }
+        ViewKind::Collection => {
+            let collection = if hashed {
+                &*state.hashed_collection
+            } else {
+                &state.collection
+            };
+            for (key, value) in keys.iter().zip(values) {
+                let entry = collection.try_load_entry(key).await?;
+                ensure!(
+                    entry.is_some_and(|entry| entry.get() == value),
+                    "wrong collection value"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Saves the state, computing first the hash of the hashed view so that it is saved too.
+async fn save_state<S>(
+    kind: ViewKind,
+    hashed: bool,
+    state: &mut BenchmarkState<ViewContext<(), S::Store>>,
+) -> Result<()>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore + 'static,
+{
+    if hashed {
+        match kind {
+            ViewKind::Register => {
+                state.hashed_register.hash_mut().await?;
+            }
+            ViewKind::Log => {
+                state.hashed_log.hash_mut().await?;
+            }
+            ViewKind::Map => {
+                state.hashed_map.hash_mut().await?;
+            }
+            ViewKind::Collection => {
+                state.hashed_collection.hash_mut().await?;
+            }
+        }
+    }
+    state.save().await?;
+    Ok(())
+}
+
+async fn new_saved_store<S>(
+    kind: ViewKind,
+    hashed: bool,
+    keys: &[Vec<u8>],
+    values: &[Vec<u8>],
+) -> Result<S::Store>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore + 'static,
+{
+    let store = S::new_test_store().await?;
+    let mut state = load_state::<S>(&store).await?;
+    set_values::<S>(kind, hashed, &mut state, keys, values).await?;
+    save_state::<S>(kind, hashed, &mut state).await?;
+    Ok(store)
+}
+
+pub async fn test_storage_views_kernel<S>(
+    name: &str,
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore + 'static,
+{
+    // Without duplicate keys, which the map and the collection would merge.
+    let key_values = get_key_values(parameters, &[]);
+    let keys = key_values.keys().cloned().collect::<Vec<_>>();
+    let values = key_values.into_values().collect::<Vec<_>>();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(135);
+    let updated_values = keys
+        .iter()
+        .map(|_| get_key(&mut rng, parameters.value_size))
+        .collect::<Vec<_>>();
+    let (keys, values, updated_values) = (&keys, &values, &updated_values);
+
+    for kind in ViewKind::ALL {
+        for hashed in [false, true] {
+            let name_of_view = kind.name(hashed);
+            let statistics = measure(settings.repetitions, || async move {
+                let store = S::new_test_store().await?;
+                let time = Instant::now();
+                let mut state = load_state::<S>(&store).await?;
+                set_values::<S>(kind, hashed, &mut state, keys, values).await?;
+                save_state::<S>(kind, hashed, &mut state).await?;
+                let elapsed = time.elapsed();
+                let state = load_state::<S>(&store).await?;
+                check_values::<S>(kind, hashed, &state, keys, values).await?;
+                Ok::<_, anyhow::Error>(elapsed)
+            })
+            .await?;
+            report.add(parameters.record(name, &name_of_view, "write", statistics));
+            //
+            let store = new_saved_store::<S>(kind, hashed, keys, values).await?;
+            let statistics = measure(settings.repetitions, || {
+                let store = store.clone();
+                async move {
+                    let time = Instant::now();
+                    let state = load_state::<S>(&store).await?;
+                    check_values::<S>(kind, hashed, &state, keys, values).await?;
+                    Ok::<_, anyhow::Error>(time.elapsed())
+                }
+            })
+            .await?;
+            report.add(parameters.record(name, &name_of_view, "read", statistics));
+            //
+            let statistics = measure(settings.repetitions, || async move {
+                let store = new_saved_store::<S>(kind, hashed, keys, values).await?;
+                let time = Instant::now();
+                let mut state = load_state::<S>(&store).await?;
+                set_values::<S>(kind, hashed, &mut state, keys, updated_values).await?;
+                save_state::<S>(kind, hashed, &mut state).await?;
+                let elapsed = time.elapsed();
+                let state = load_state::<S>(&store).await?;
+                check_values::<S>(kind, hashed, &state, keys, updated_values).await?;
+                Ok::<_, anyhow::Error>(elapsed)
+            })
+            .await?;
+            report.add(parameters.record(name, &name_of_view, "update", statistics));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use linera_views::memory::MemoryDatabase;
+
+    use super::*;
+    use crate::{report::OutputFormat, Args};
+
+    #[tokio::test]
+    async fn test_views_kernel_with_duplicate_keys() -> Result<()> {
+        let args = Args::parse_from([
+            "benchmark",
+            "300",
+            "1",
+            "4",
+            "--warmup-runs",
+            "0",
+            "--runs",
+            "1",
+        ]);
+        // More keys than the 256 keys of one byte, so that some are drawn twice.
+        let parameters = Parameters {
+            num_key: 300,
+            key_size: 1,
+            value_size: 4,
+        };
+        let mut report = Report::new(OutputFormat::Json);
+        test_storage_views_kernel::<MemoryDatabase>(
+            "memory",
+            &Settings::new(&args),
+            parameters,
+            &mut report,
+        )
+        .await?;
+        // Three phases for the plain and the hashed version of each view.
+        assert_eq!(report.records().len(), 2 * 3 * ViewKind::ALL.len());
+        let operations = report
+            .records()
+            .iter()
+            .map(|record| record.operation.as_str())
+            .collect::<Vec<_>>();
+        assert!(operations.contains(&"map-view"));
+        assert!(operations.contains(&"hashed-map-view"));
+        Ok(())
+    }
+}
@@ -0,0 +1,393 @@
+//! A YCSB-style mixed workload: concurrent clients issue reads, writes and prefix
+//! scans on a preloaded store, with keys drawn from a uniform or Zipfian distribution.
+
+use std::cell::RefCell;
+
+use anyhow::{ensure, Result};
+use benchmark_statistics::latency_histogram;
+use clap::ValueEnum;
+use linera_base::time::Instant;
+use linera_views::{
+    batch::Batch,
+    store::{KeyValueStore, ReadableKeyValueStore, TestKeyValueDatabase, WritableKeyValueStore},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{get_key, measure, statistics::Statistics, Parameters, Report, Settings};
+
+/// The YCSB Zipfian constant.
+const ZIPFIAN_CONSTANT: f64 = 0.99;
+
+/// How the accessed keys are chosen among the preloaded ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum KeyDistribution {
+    Uniform,
+    /// A few keys get most of the accesses, as in YCSB.
+    Zipfian,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct MixedWorkload {
+    /// Relative weight of the reads in the mixed workload.
+    #[arg(long, default_value_t = 50)]
+    pub read_ratio: u32,
+
+    /// Relative weight of the writes in the mixed workload.
+    #[arg(long, default_value_t = 50)]
+    pub write_ratio: u32,
+
+    /// Relative weight of the prefix scans in the mixed workload. A scan reads the
+    /// keys sharing the first byte of the chosen key, i.e. about `num_key / 256` entries.
+    #[arg(long, default_value_t = 0)]
+    pub scan_ratio: u32,
+
+    /// Distribution of the accessed keys in the mixed workload.
+    #[arg(long, value_enum, default_value_t = KeyDistribution::Zipfian)]
+    pub distribution: KeyDistribution,
+
+    /// Number of clients issuing operations at the same time in the mixed workload.
+    #[arg(long, default_value_t = 8, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub concurrency: usize,
+
+    /// Number of operations of each run of the mixed workload. Like the other kernels, the
+    /// workload is run `--warmup-runs` times and then measured `--runs` times.
+    #[arg(long, default_value_t = 10000)]
+    pub workload_operations: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OperationKind {
+    Read,
+    Write,
+    Scan,
+}
+
+impl OperationKind {
+    const ALL: [OperationKind; 3] = [
+        OperationKind::Read,
+        OperationKind::Write,
+        OperationKind::Scan,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            OperationKind::Read => "mixed-read",
+            OperationKind::Write => "mixed-write",
+            OperationKind::Scan => "mixed-scan",
+        }
+    }
+}
+
+/// Generates indices in `0..num_items` following a Zipfian distribution, using the
+/// algorithm of Gray et al., "Quickly generating billion-record synthetic databases",
+/// like the YCSB `ZipfianGenerator`.
+struct ZipfianGenerator {
+    num_items: usize,
+    theta: f64,
+    alpha: f64,
+    zetan: f64,
+    eta: f64,
+}
+
+impl ZipfianGenerator {
+    fn new(num_items: usize, theta: f64) -> Self {
+        let zeta = |n: usize| (1..=n).map(|i| 1.0 / (i as f64).powf(theta)).sum::<f64>();
+        let zetan = zeta(num_items);
+        let zeta2 = zeta(2);
+        let eta = (1.0 - (2.0 / num_items as f64).powf(1.0 - theta)) / (1.0 - zeta2 / zetan);
+        Self {
+            num_items,
+            theta,
+            alpha: 1.0 / (1.0 - theta),
+            zetan,
+            eta,
+        }
+    }
+
+    fn next(&self, rng: &mut impl Rng) -> usize {
+        let u = rng.gen::<f64>();
+        let uz = u * self.zetan;
+        if uz < 1.0 {
+            return 0;
+        }
+        if uz < 1.0 + 0.5_f64.powf(self.theta) {
+            return 1.min(self.num_items - 1);
+        }
+        let index =
+            (self.num_items as f64 * (self.eta * u - self.eta + 1.0).powf(self.alpha)) as usize;
+        index.min(self.num_items - 1)
+    }
+}
+
+enum KeyChooser {
+    Uniform(usize),
+    Zipfian(ZipfianGenerator),
+}
+
+impl KeyChooser {
+    fn next(&self, rng: &mut impl Rng) -> usize {
+        match self {
+            KeyChooser::Uniform(num_items) => rng.gen_range(0..*num_items),
+            KeyChooser::Zipfian(generator) => generator.next(rng),
+        }
+    }
+}
+
+impl MixedWorkload {
+    fn strategy(&self) -> String {
+        let distribution = match self.distribution {
+            KeyDistribution::Uniform => "uniform",
+            KeyDistribution::Zipfian => "zipfian",
+        };
+        format!("{distribution}-c{}", self.concurrency)
+    }
+
+    /// The sum of the ratios, which cannot overflow as a `u64`.
+    fn total_ratio(&self) -> u64 {
+        u64::from(self.read_ratio) + u64::from(self.write_ratio) + u64::from(self.scan_ratio)
+    }
+
+    fn choose_operation(&self, rng: &mut impl Rng) -> OperationKind {
+        let choice = rng.gen_range(0..self.total_ratio());
+        if choice < u64::from(self.read_ratio) {
+            OperationKind::Read
+        } else if choice < u64::from(self.read_ratio) + u64::from(self.write_ratio) {
+            OperationKind::Write
+        } else {
+            OperationKind::Scan
+        }
+    }
+}
+
+pub async fn test_storage_mixed_workload_kernel<S>(
+    name: &str,
+    settings: &Settings,
+    parameters: Parameters,
+    report: &mut Report,
+) -> Result<()>
+where
+    S: TestKeyValueDatabase,
+    S::Store: Clone + KeyValueStore,
+{
+    let workload = &settings.workload;
+    ensure!(
+        workload.total_ratio() > 0,
+        "at least one of the mixed workload ratios must be positive"
+    );
+    ensure!(
+        parameters.num_key > 0 && parameters.key_size > 0,
+        "the mixed workload needs a non-empty data set"
+    );
+    let mut rng = StdRng::seed_from_u64(134 as u64);
+    let store = S::new_test_store().await?;
+    let mut batch = Batch::new();
+    let mut keys = Vec::new();
+    for _ in 0..parameters.num_key {
+        let key = get_key(&mut rng, parameters.key_size);
+        let value = get_key(&mut rng, parameters.value_size);
+        keys.push(key.clone());
+        batch.put_key_value_bytes(key, value);
+    }
+    store.write_batch(batch).await?;
+    let chooser = match workload.distribution {
+        KeyDistribution::Uniform => KeyChooser::Uniform(keys.len()),
+        KeyDistribution::Zipfian => {
+            KeyChooser::Zipfian(ZipfianGenerator::new(keys.len(), ZIPFIAN_CONSTANT))
+        }
+    };
+
+    // The writes of a run stay in the store for the next runs, as the data set evolves
+    // in a long-running YCSB workload.
+    let runs = RefCell::new(Vec::new());
+    let statistics = measure(settings.repetitions, || {
+        let (store, keys, chooser, runs) = (&store, &keys, &chooser, &runs);
+        async move {
+            let seed = 1000 * (runs.borrow().len() as u64 + 1);
+            let time = Instant::now();
+            let latencies =
+                run_clients(store, keys, chooser, workload, parameters.value_size, seed).await?;
+            let elapsed = time.elapsed();
+            runs.borrow_mut().push(latencies);
+            Ok::<_, anyhow::Error>(elapsed)
+        }
+    })
+    .await?;
+    let latencies = runs
+        .into_inner()
+        .into_iter()
+        .skip(settings.repetitions.warmup_runs)
+        .flatten()
+        .collect::<Vec<_>>();
+    let elapsed = statistics.mean * statistics.runs as f64 / 1_000_000.0;
+    let throughput = latencies.len() as f64 / elapsed;
+    report.log(&format!(
+        "Throughput {name} for mixed workload {}: {throughput:.0} ops/sec",
+        workload.strategy()
+    ));
+    let mut record = parameters.record(name, "mixed", &workload.strategy(), statistics);
+    record.throughput = Some(throughput);
+    report.add(record);
+
+    for kind in OperationKind::ALL {
+        let samples = latencies
+            .iter()
+            .filter(|(other, _)| *other == kind)
+            .map(|(_, latency)| *latency)
+            .collect::<Vec<_>>();
+        if samples.is_empty() {
+            continue;
+        }
+        let mut record = parameters.record(
+            name,
+            kind.name(),
+            &workload.strategy(),
+            Statistics::new(&samples),
+        );
+        record.throughput = Some(samples.len() as f64 / elapsed);
+        report.add(record);
+        for (bound, count) in latency_histogram(&samples) {
+            report.log(&format!("    <= {bound:>9} micros: {count}"));
+        }
+    }
+    Ok(())
+}
+
+/// Runs `workload.workload_operations` operations split among `workload.concurrency`
+/// clients, and returns the kind and the latency in microseconds of each of them.
+async fn run_clients<S>(
+    store: &S,
+    keys: &[Vec<u8>],
+    chooser: &KeyChooser,
+    workload: &MixedWorkload,
+    value_size: usize,
+    seed: u64,
+) -> Result<Vec<(OperationKind, f64)>>
+where
+    S: Clone + KeyValueStore,
+{
+    let mut futures = Vec::new();
+    for client in 0..workload.concurrency {
+        let num_operations = workload.workload_operations / workload.concurrency
+            + usize::from(client < workload.workload_operations % workload.concurrency);
+        let store = store.clone();
+        futures.push(async move {
+            let mut rng = StdRng::seed_from_u64(seed + client as u64);
+            let mut latencies = Vec::new();
+            for _ in 0..num_operations {
+                let kind = workload.choose_operation(&mut rng);
+                let key = &keys[chooser.next(&mut rng)];
+                // The written value is generated before starting the timer.
+                let mut batch = Batch::new();
+                if kind == OperationKind::Write {
+                    batch.put_key_value_bytes(key.clone(), get_key(&mut rng, value_size));
+                }
+                let time = Instant::now();
+                match kind {
+                    OperationKind::Read => {
+                        store.read_value_bytes(key).await?;
+                    }
+                    OperationKind::Write => {
+                        store.write_batch(batch).await?;
+                    }
+                    OperationKind::Scan => {
+                        store.find_key_values_by_prefix(&key[..1]).await?;
+                    }
+                }
+                latencies.push((kind, time.elapsed().as_micros() as f64));
+            }
+            Ok::<_, anyhow::Error>(latencies)
+        });
+    }
+    let latencies = futures::future::try_join_all(futures).await?;
+    Ok(latencies.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use linera_views::memory::MemoryDatabase;
+
+    use super::*;
+    use crate::{report::OutputFormat, Args};
+
+    #[tokio::test]
+    async fn test_mixed_workload_kernel() -> Result<()> {
+        let args = Args::parse_from([
+            "benchmark",
+            "100",
+            "4",
+            "8",
+            "--warmup-runs",
+            "1",
+            "--runs",
+            "3",
+            "--workload-operations",
+            "50",
+            "--scan-ratio",
+            "10",
+        ]);
+        let settings = Settings::new(&args);
+        let parameters = Parameters {
+            num_key: 100,
+            key_size: 4,
+            value_size: 8,
+        };
+        let mut report = Report::new(OutputFormat::Json);
+        test_storage_mixed_workload_kernel::<MemoryDatabase>(
+            "memory",
+            &settings,
+            parameters,
+            &mut report,
+        )
+        .await?;
+        let records = report.records();
+        assert_eq!(records[0].operation, "mixed");
+        assert_eq!(records[0].strategy, "zipfian-c8");
+        assert_eq!(records[0].statistics.runs, 3);
+        // The operations of the three measured runs, without the warm-up run.
+        let num_operations = records[1..]
+            .iter()
+            .map(|record| record.statistics.runs)
+            .sum::<usize>();
+        assert_eq!(num_operations, 150);
+        Ok(())
+    }
+
+    #[test]
+    fn test_zipfian_generator() {
+        let generator = ZipfianGenerator::new(100, ZIPFIAN_CONSTANT);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut counts = vec![0; 100];
+        for _ in 0..10_000 {
+            counts[generator.next(&mut rng)] += 1;
+        }
+        assert_eq!(counts.iter().max(), Some(&counts[0]));
+        assert!(counts[..10].iter().sum::<usize>() > 5 * counts[90..].iter().sum::<usize>());
+    }
+
+    #[test]
+    fn test_choose_operation_with_large_ratios() {
+        let workload = MixedWorkload {
+            read_ratio: u32::MAX,
+            write_ratio: u32::MAX,
+            scan_ratio: 0,
+            distribution: KeyDistribution::Uniform,
+            concurrency: 1,
+            workload_operations: 1,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let kinds = (0..100)
+            .map(|_| workload.choose_operation(&mut rng))
+            .collect::<Vec<_>>();
+        assert!(kinds.contains(&OperationKind::Read));
+        assert!(kinds.contains(&OperationKind::Write));
+        assert!(!kinds.contains(&OperationKind::Scan));
+    }
+
+    #[test]
+    fn test_zipfian_generator_with_one_item() {
+        let generator = ZipfianGenerator::new(1, ZIPFIAN_CONSTANT);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!((0..100).all(|_| generator.next(&mut rng) == 0));
+    }
+}
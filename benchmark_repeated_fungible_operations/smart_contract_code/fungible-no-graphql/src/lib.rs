@@ -3,9 +3,9 @@
 
 /* ABI of the Fungible Token Example Application */
 
+use linera_sdk::abi::{ContractAbi, ServiceAbi};
 pub use linera_sdk::abis::fungible::*;
 use linera_sdk::linera_base_types::{Account, AccountOwner, Amount};
-use linera_sdk::abi::{ContractAbi, ServiceAbi};
 use serde::{Deserialize, Serialize};
 #[cfg(all(any(test, feature = "test"), not(target_arch = "wasm32")))]
 use {
@@ -48,11 +48,10 @@ pub enum FungibleNoGraphQlRequest {
         /// The operation in question.
         operations: Vec<FungibleOperation>,
     },
+    /// The total balance of the accounts on this chain.
+    TotalBalance,
 }
 
-
-
-
 /// A message.
 #[derive(Debug, Deserialize, Serialize)]
 pub enum Message {
@@ -94,4 +93,3 @@ impl OwnerSpender {
         Self { owner, spender }
     }
 }
-
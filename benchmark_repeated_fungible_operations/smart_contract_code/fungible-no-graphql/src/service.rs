@@ -7,9 +7,9 @@ mod state;
 
 use std::sync::Arc;
 
-use fungible_no_graphql::FungibleNoGraphQlTokenAbi;
-use fungible_no_graphql::FungibleNoGraphQlRequest;
 use fungible::{OwnerSpender, Parameters};
+use fungible_no_graphql::FungibleNoGraphQlRequest;
+use fungible_no_graphql::FungibleNoGraphQlTokenAbi;
 use linera_sdk::{
     linera_base_types::{Amount, WithServiceAbi},
     views::View,
@@ -47,7 +47,7 @@ impl Service for FungibleTokenService {
         match request {
             FungibleNoGraphQlRequest::Balance { owner } => {
                 self.state.balance_or_default(&owner).await
-            },
+            }
             FungibleNoGraphQlRequest::Allowance { owner, spender } => {
                 let owner_spender = OwnerSpender::new(owner, spender);
                 self.state
@@ -56,17 +56,30 @@ impl Service for FungibleTokenService {
                     .await
                     .expect("Failure in the retrieval")
                     .unwrap_or_default()
-            },
+            }
             FungibleNoGraphQlRequest::Operation { operation } => {
                 self.runtime.schedule_operation(&operation);
                 Amount::ZERO
-            },
+            }
             FungibleNoGraphQlRequest::Operations { operations } => {
                 for operation in operations {
                     self.runtime.schedule_operation(&operation);
                 }
                 Amount::ZERO
-            },
+            }
+            FungibleNoGraphQlRequest::TotalBalance => {
+                let owners = self
+                    .state
+                    .accounts
+                    .indices()
+                    .await
+                    .expect("Failure in the retrieval");
+                let mut total = Amount::ZERO;
+                for owner in owners {
+                    total.saturating_add_assign(self.state.balance_or_default(&owner).await);
+                }
+                total
+            }
         }
     }
 }
@@ -0,0 +1,102 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Integration tests for the Fungible Token application without GraphQL.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use fungible_no_graphql::{
+    FungibleNoGraphQlRequest, FungibleNoGraphQlTokenAbi, FungibleOperation, InitialState,
+    InitialStateBuilder, Parameters,
+};
+use linera_sdk::{
+    linera_base_types::{Account, AccountOwner, Amount, ApplicationId},
+    test::{ActiveChain, MessageAction, QueryOutcome, TestValidator},
+};
+
+/// Queries the balance of an account owned by `owner` on a specific `chain`.
+async fn query_balance(
+    application_id: ApplicationId<FungibleNoGraphQlTokenAbi>,
+    chain: &ActiveChain,
+    owner: AccountOwner,
+) -> Amount {
+    let QueryOutcome { response, .. } = chain
+        .query(application_id, FungibleNoGraphQlRequest::Balance { owner })
+        .await;
+    response
+}
+
+/// Test bouncing claimed tokens back to the chain they were claimed from.
+///
+/// Creates the application on a `source_chain`, initializing it with an account of the owner
+/// of a `claimer_chain`. The claimer claims some of those tokens, which sends a `Withdraw`
+/// message to the `source_chain`, answered by a `Credit` message. The `claimer_chain` rejects
+/// the `Credit` message, so the tokens are returned to the account on the `source_chain`.
+#[tokio::test]
+async fn test_bouncing_claimed_tokens() {
+    let initial_amount = Amount::from_tokens(19);
+    let claim_amount = Amount::from_tokens(7);
+
+    let (validator, module_id) =
+        TestValidator::with_current_module::<FungibleNoGraphQlTokenAbi, Parameters, InitialState>()
+            .await;
+    let mut source_chain = validator.new_chain().await;
+    let claimer_chain = validator.new_chain().await;
+    let claimer = AccountOwner::from(claimer_chain.public_key());
+
+    let initial_state = InitialStateBuilder::default().with_account(claimer, initial_amount);
+    let params = Parameters::new("RET");
+    let application_id = source_chain
+        .create_application(module_id, params, initial_state.build(), vec![])
+        .await;
+
+    let claim_certificate = claimer_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                FungibleOperation::Claim {
+                    source_account: Account {
+                        chain_id: source_chain.id(),
+                        owner: claimer,
+                    },
+                    amount: claim_amount,
+                    target_account: Account {
+                        chain_id: claimer_chain.id(),
+                        owner: claimer,
+                    },
+                },
+            );
+        })
+        .await;
+
+    let withdraw_certificate = source_chain
+        .add_block(move |block| {
+            block.with_messages_from(&claim_certificate);
+        })
+        .await;
+
+    assert_eq!(
+        query_balance(application_id, &source_chain, claimer).await,
+        initial_amount.saturating_sub(claim_amount),
+    );
+
+    assert_eq!(withdraw_certificate.outgoing_message_count(), 1);
+
+    claimer_chain
+        .add_block(move |block| {
+            block.with_messages_from_by_action(&withdraw_certificate, MessageAction::Reject);
+        })
+        .await;
+
+    assert_eq!(
+        query_balance(application_id, &claimer_chain, claimer).await,
+        Amount::ZERO,
+    );
+
+    source_chain.handle_received_messages().await;
+
+    assert_eq!(
+        query_balance(application_id, &source_chain, claimer).await,
+        initial_amount,
+    );
+}
@@ -0,0 +1,285 @@
+use anyhow::{ensure, Result};
+use clap::ValueEnum;
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ChainId},
+};
+use linera_service::cli_wrappers::{ClientWrapper, NodeService};
+
+/// How the initial balances are spread over the senders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BalanceDistribution {
+    /// Every sender gets `--initial-balance`.
+    Equal,
+    /// Sender `i` gets `(i + 1) * --initial-balance`.
+    Linear,
+    /// Sender `i` gets `2^i * --initial-balance`.
+    Geometric,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct FanOut {
+    /// Number of transfers done by each benchmark.
+    #[arg(long, global = true, default_value_t = 500)]
+    pub num_operations: usize,
+
+    /// Number of distinct senders. The first one is the owner of the default chain. Since a
+    /// block has a single signer, each additional sender gets its own chain.
+    #[arg(long, global = true, default_value_t = 1, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub num_senders: usize,
+
+    /// Number of distinct receivers, all on the default chain unless `--cross-chain` is set.
+    #[arg(long, global = true, default_value_t = 1, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub num_receivers: usize,
+
+    /// Initial balance of the senders, in tokens.
+    #[arg(long, global = true, default_value_t = 1000)]
+    pub initial_balance: u128,
+
+    /// How the initial balances are spread over the senders.
+    #[arg(long, global = true, value_enum, default_value_t = BalanceDistribution::Equal)]
+    pub balance_distribution: BalanceDistribution,
+
+    /// Put the receivers on a second chain and include in the measured time the
+    /// processing of its inbox, i.e. the delivery of the credits.
+    #[arg(long, global = true)]
+    pub cross_chain: bool,
+}
+
+/// The accounts taking part in a benchmark.
+pub struct Accounts {
+    /// The default chain of the client, holding the first sender.
+    pub chain_id: ChainId,
+    /// The chain of the receivers: the default chain, or a second one with `--cross-chain`.
+    pub receiver_chain_id: ChainId,
+    pub senders: Vec<Account>,
+    pub receivers: Vec<Account>,
+    /// The initial balance of each sender.
+    pub balances: Vec<Amount>,
+    pub num_operations: usize,
+}
+
+impl FanOut {
+    fn balance(&self, index: usize) -> Amount {
+        let factor = match self.balance_distribution {
+            BalanceDistribution::Equal => 1,
+            BalanceDistribution::Linear => index as u128 + 1,
+            BalanceDistribution::Geometric => 1u128 << index.min(64),
+        };
+        Amount::from_tokens(self.initial_balance.saturating_mul(factor))
+    }
+
+    /// Creates the senders and the receivers. This has to be done before starting the
+    /// node service, so that it serves the chains of the additional senders.
+    pub async fn create_accounts(&self, client: &ClientWrapper) -> Result<Accounts> {
+        let chain_id = client.load_wallet()?.default_chain().unwrap();
+        let mut senders = vec![Account {
+            chain_id,
+            owner: client.get_owner().unwrap(),
+        }];
+        for _ in 1..self.num_senders {
+            let (sender_chain_id, owner) = client
+                .open_chain(chain_id, None, Amount::from_tokens(10))
+                .await?;
+            senders.push(Account {
+                chain_id: sender_chain_id,
+                owner,
+            });
+        }
+        let receiver_chain_id = if self.cross_chain {
+            client
+                .open_chain(chain_id, None, Amount::from_tokens(10))
+                .await?
+                .0
+        } else {
+            chain_id
+        };
+        let mut receivers = Vec::new();
+        for _ in 0..self.num_receivers {
+            receivers.push(Account {
+                chain_id: receiver_chain_id,
+                owner: client.keygen().await?,
+            });
+        }
+        let balances = (0..self.num_senders)
+            .map(|index| self.balance(index))
+            .collect::<Vec<_>>();
+        let accounts = Accounts {
+            chain_id,
+            receiver_chain_id,
+            senders,
+            receivers,
+            balances,
+            num_operations: self.num_operations,
+        };
+        for (index, sender) in accounts.senders.iter().enumerate() {
+            let needed = accounts.transfers_of_sender(index).len() as u128;
+            ensure!(
+                accounts.balances[index] >= Amount::from_tokens(needed),
+                "sender {index} ({}) needs {needed} tokens but only gets {}",
+                sender.owner,
+                accounts.balances[index]
+            );
+        }
+        Ok(accounts)
+    }
+}
+
+impl Accounts {
+    /// The total of the initial balances.
+    pub fn total_balance(&self) -> Amount {
+        self.balances.iter().fold(Amount::ZERO, |total, balance| {
+            total.saturating_add(*balance)
+        })
+    }
+
+    /// The chains of the senders and of the receivers.
+    pub fn chain_ids(&self) -> Vec<ChainId> {
+        let mut chain_ids = self
+            .senders
+            .iter()
+            .map(|sender| sender.chain_id)
+            .collect::<Vec<_>>();
+        chain_ids.push(self.receiver_chain_id);
+        chain_ids.sort();
+        chain_ids.dedup();
+        chain_ids
+    }
+
+    /// The senders other than the first one, each on its own chain, with their balances.
+    pub fn remote_senders(&self) -> impl Iterator<Item = (&Account, Amount)> {
+        self.senders
+            .iter()
+            .zip(self.balances.iter().copied())
+            .skip(1)
+    }
+
+    /// The receivers of the transfers of the sender `index`: transfer `k` goes from
+    /// sender `k % num_senders` to receiver `k % num_receivers`.
+    pub fn transfers_of_sender(&self, index: usize) -> Vec<Account> {
+        (0..self.num_operations)
+            .filter(|k| k % self.senders.len() == index)
+            .map(|k| self.receivers[k % self.receivers.len()])
+            .collect()
+    }
+
+    /// Processes the inboxes of the chains of the additional senders, e.g. after funding them.
+    pub async fn process_sender_inboxes(&self, node_service: &NodeService) -> Result<()> {
+        for (sender, _) in self.remote_senders() {
+            node_service.process_inbox(&sender.chain_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Whether some transfers go to another chain, so that the receivers only get the
+    /// credits once their inbox is processed.
+    fn has_remote_transfers(&self) -> bool {
+        self.senders
+            .iter()
+            .any(|sender| sender.chain_id != self.receiver_chain_id)
+    }
+
+    /// Processes the inbox of the chain of the receivers, delivering the credits sent from other chains.
+    pub async fn process_receiver_inbox(&self, node_service: &NodeService) -> Result<()> {
+        if self.has_remote_transfers() {
+            node_service.process_inbox(&self.receiver_chain_id).await?;
+        }
+        Ok(())
+    }
+
+    /// The suffix of the printed variant names.
+    pub fn label(&self) -> &'static str {
+        if self.receiver_chain_id != self.chain_id {
+            " cross-chain"
+        } else {
+            ""
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_base::{crypto::CryptoHash, identifiers::AccountOwner};
+
+    use super::*;
+
+    fn account(chain_id: ChainId, name: String) -> Account {
+        Account {
+            chain_id,
+            owner: AccountOwner::Address32(CryptoHash::test_hash(name)),
+        }
+    }
+
+    /// Two senders and three receivers on one chain, with 10 tokens each.
+    fn accounts(num_operations: usize) -> Accounts {
+        let chain_id = ChainId(CryptoHash::test_hash("chain"));
+        Accounts {
+            chain_id,
+            receiver_chain_id: chain_id,
+            senders: (0..2)
+                .map(|index| account(chain_id, format!("sender {index}")))
+                .collect(),
+            receivers: (0..3)
+                .map(|index| account(chain_id, format!("receiver {index}")))
+                .collect(),
+            balances: vec![Amount::from_tokens(10); 2],
+            num_operations,
+        }
+    }
+
+    #[test]
+    fn test_transfers_of_sender() {
+        let accounts = accounts(7);
+        let receivers = &accounts.receivers;
+        assert_eq!(
+            accounts.transfers_of_sender(0),
+            [receivers[0], receivers[2], receivers[1], receivers[0]]
+        );
+        assert_eq!(
+            accounts.transfers_of_sender(1),
+            [receivers[1], receivers[0], receivers[2]]
+        );
+        assert_eq!(accounts.total_balance(), Amount::from_tokens(20));
+        assert_eq!(accounts.chain_ids(), [accounts.chain_id]);
+        assert_eq!(accounts.label(), "");
+    }
+
+    #[test]
+    fn test_expected_balances() {
+        let accounts = accounts(7);
+        let initial = accounts
+            .all_accounts()
+            .into_iter()
+            .map(|account| (account, Amount::from_tokens(10)))
+            .collect::<Vec<_>>();
+        let expected = accounts
+            .expected_balances(&initial, Amount::ONE)
+            .into_iter()
+            .map(|(_, balance)| balance)
+            .collect::<Vec<_>>();
+        let tokens = [6, 7, 13, 12, 12].map(Amount::from_tokens);
+        assert_eq!(expected, tokens);
+    }
+
+    #[test]
+    fn test_balance_distribution() {
+        let mut fan_out = FanOut {
+            num_operations: 10,
+            num_senders: 3,
+            num_receivers: 1,
+            initial_balance: 10,
+            balance_distribution: BalanceDistribution::Equal,
+            cross_chain: false,
+        };
+        let balances = |fan_out: &FanOut| {
+            (0..3)
+                .map(|index| fan_out.balance(index))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(balances(&fan_out), [10, 10, 10].map(Amount::from_tokens));
+        fan_out.balance_distribution = BalanceDistribution::Linear;
+        assert_eq!(balances(&fan_out), [10, 20, 30].map(Amount::from_tokens));
+        fan_out.balance_distribution = BalanceDistribution::Geometric;
+        assert_eq!(balances(&fan_out), [10, 20, 40].map(Amount::from_tokens));
+    }
+}
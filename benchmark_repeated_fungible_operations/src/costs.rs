@@ -0,0 +1,185 @@
+//! Fees and execution costs of the benchmarked transfers. The fees are the decrease of the
+//! balances of the chains involved. The fuel and storage counters come from the Prometheus
+//! endpoints of the validator shards, when the binaries are built with metrics.
+
+use std::fmt;
+
+use anyhow::Result;
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, AccountOwner, ChainId},
+};
+use linera_service::cli_wrappers::{local_net::LocalNetConfig, NodeService};
+
+/// The histograms of `linera-execution`, of which the sums are accumulated. All the
+/// Linera metrics are in the `linera` namespace.
+const FUEL_METRIC: &str = "linera_wasm_fuel_used_per_block_sum";
+const NUM_READS_METRIC: &str = "linera_wasm_num_reads_per_block_sum";
+const BYTES_READ_METRIC: &str = "linera_wasm_bytes_read_per_block_sum";
+const BYTES_WRITTEN_METRIC: &str = "linera_wasm_bytes_written_per_block_sum";
+
+/// The metrics port of a shard of a validator of the local net. `LocalNet` has no public
+/// accessor for it, so this mirrors its private `shard_metrics_port` and has to follow
+/// any change of the port layout of `linera-service`.
+fn shard_metrics_port(validator: usize, shard: usize, num_shards: usize) -> usize {
+    11000 + validator * num_shards + shard + 1
+}
+
+/// The execution counters summed over the shards of all the validators.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExecutionMetrics {
+    pub fuel: f64,
+    pub num_reads: f64,
+    pub bytes_read: f64,
+    pub bytes_written: f64,
+}
+
+impl ExecutionMetrics {
+    fn parse(&mut self, text: &str) {
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let Some((name, value)) = line.rsplit_once(' ') else {
+                continue;
+            };
+            let Ok(value) = value.parse::<f64>() else {
+                continue;
+            };
+            match name.split('{').next().unwrap_or(name) {
+                FUEL_METRIC => self.fuel += value,
+                NUM_READS_METRIC => self.num_reads += value,
+                BYTES_READ_METRIC => self.bytes_read += value,
+                BYTES_WRITTEN_METRIC => self.bytes_written += value,
+                _ => {}
+            }
+        }
+    }
+
+    /// The counters accumulated since `before`, divided by the number of validators
+    /// since every validator executes every block.
+    fn per_validator_since(
+        &self,
+        before: &ExecutionMetrics,
+        num_validators: usize,
+    ) -> ExecutionMetrics {
+        let num_validators = num_validators.max(1) as f64;
+        ExecutionMetrics {
+            fuel: (self.fuel - before.fuel) / num_validators,
+            num_reads: (self.num_reads - before.num_reads) / num_validators,
+            bytes_read: (self.bytes_read - before.bytes_read) / num_validators,
+            bytes_written: (self.bytes_written - before.bytes_written) / num_validators,
+        }
+    }
+}
+
+/// The state from which the costs are computed.
+pub struct CostSnapshot {
+    total_balance: Amount,
+    metrics: Option<ExecutionMetrics>,
+}
+
+/// The costs of the transfers of one benchmark variant.
+#[derive(Clone, Copy, Debug)]
+pub struct Costs {
+    pub fees: Amount,
+    pub metrics: Option<ExecutionMetrics>,
+}
+
+impl fmt::Display for Costs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "fees={}", self.fees)?;
+        match &self.metrics {
+            Some(metrics) => write!(
+                f,
+                " fuel={:.0} num_reads={:.0} bytes_read={:.0} bytes_written={:.0} (per validator)",
+                metrics.fuel, metrics.num_reads, metrics.bytes_read, metrics.bytes_written
+            ),
+            None => write!(f, " (no execution metrics exposed by the validators)"),
+        }
+    }
+}
+
+pub struct CostTracker {
+    metrics_urls: Vec<String>,
+    num_validators: usize,
+}
+
+impl CostTracker {
+    /// Scrapes the shards of the local net.
+    pub fn for_local_net(config: &LocalNetConfig) -> Self {
+        let num_validators = config.num_initial_validators;
+        let num_shards = config.num_shards;
+        let metrics_urls = (0..num_validators)
+            .flat_map(|validator| {
+                (0..num_shards).map(move |shard| {
+                    let port = shard_metrics_port(validator, shard, num_shards);
+                    format!("http://127.0.0.1:{port}/metrics")
+                })
+            })
+            .collect();
+        Self {
+            metrics_urls,
+            num_validators,
+        }
+    }
+
+    /// Returns `None` if no shard exposes metrics.
+    async fn scrape(&self) -> Option<ExecutionMetrics> {
+        let mut metrics = ExecutionMetrics::default();
+        let mut found = false;
+        for url in &self.metrics_urls {
+            let Ok(response) = reqwest::get(url).await else {
+                continue;
+            };
+            let Ok(text) = response.text().await else {
+                continue;
+            };
+            metrics.parse(&text);
+            found = true;
+        }
+        found.then_some(metrics)
+    }
+
+    pub async fn snapshot(
+        &self,
+        node_service: &NodeService,
+        chain_ids: &[ChainId],
+    ) -> Result<CostSnapshot> {
+        let chains = chain_ids
+            .iter()
+            .map(|chain_id| (node_service, *chain_id))
+            .collect::<Vec<_>>();
+        self.snapshot_chains(&chains).await
+    }
+
+    /// Same as `snapshot`, for chains served by different node services.
+    pub async fn snapshot_chains(
+        &self,
+        chains: &[(&NodeService, ChainId)],
+    ) -> Result<CostSnapshot> {
+        let mut total_balance = Amount::ZERO;
+        for (node_service, chain_id) in chains {
+            let account = Account {
+                chain_id: *chain_id,
+                owner: AccountOwner::CHAIN,
+            };
+            total_balance = total_balance.saturating_add(node_service.balance(&account).await?);
+        }
+        Ok(CostSnapshot {
+            total_balance,
+            metrics: self.scrape().await,
+        })
+    }
+
+    /// The costs between two snapshots of the same chains.
+    pub fn costs(&self, before: &CostSnapshot, after: &CostSnapshot) -> Costs {
+        let metrics = match (&before.metrics, &after.metrics) {
+            (Some(before), Some(after)) => {
+                Some(after.per_validator_since(before, self.num_validators))
+            }
+            _ => None,
+        };
+        Costs {
+            fees: before.total_balance.saturating_sub(after.total_balance),
+            metrics,
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! GraphQL documents built from typed values. The mutations of the fungible applications
+//! are produced from `FungibleOperation`, the same values that the fungible-no-graphql
+//! application receives in BCS/JSON, so that both paths submit the same operations. The
+//! tests check it by decoding the mutations back into operations.
+
+use anyhow::{bail, Result};
+use linera_base::{
+    async_graphql::InputType,
+    data_types::Amount,
+    identifiers::{Account, AccountOwner, ChainId},
+};
+use linera_sdk::abis::fungible::FungibleOperation;
+use linera_service::cli_wrappers::ApplicationWrapper;
+
+/// The mutation field of the fungible and native-fungible applications for `operation`.
+pub fn operation_mutation(operation: &FungibleOperation) -> String {
+    match operation {
+        FungibleOperation::Balance { owner } => format!("balance(owner: {})", owner.to_value()),
+        FungibleOperation::TickerSymbol => "tickerSymbol".to_string(),
+        FungibleOperation::Approve {
+            owner,
+            spender,
+            allowance,
+        } => format!(
+            "approve(owner: {}, spender: {}, allowance: \"{allowance}\")",
+            owner.to_value(),
+            spender.to_value()
+        ),
+        FungibleOperation::Transfer {
+            owner,
+            amount,
+            target_account,
+        } => format!(
+            "transfer(owner: {}, amount: \"{amount}\", targetAccount: {})",
+            owner.to_value(),
+            target_account.to_value()
+        ),
+        FungibleOperation::TransferFrom {
+            owner,
+            spender,
+            amount,
+            target_account,
+        } => format!(
+            "transferFrom(owner: {}, spender: {}, amount: \"{amount}\", targetAccount: {})",
+            owner.to_value(),
+            spender.to_value(),
+            target_account.to_value()
+        ),
+        FungibleOperation::Claim {
+            source_account,
+            amount,
+            target_account,
+        } => format!(
+            "claim(sourceAccount: {}, amount: \"{amount}\", targetAccount: {})",
+            source_account.to_value(),
+            target_account.to_value()
+        ),
+    }
+}
+
+/// The `transfer` mutation of the node service, moving native tokens.
+pub fn native_transfer_mutation(
+    chain_id: ChainId,
+    owner: AccountOwner,
+    recipient: &Account,
+    amount: Amount,
+) -> String {
+    format!(
+        "transfer(chainId: \"{chain_id}\", owner: {}, recipient: {}, amount: \"{amount}\")",
+        owner.to_value(),
+        recipient.to_value()
+    )
+}
+
+/// One mutation document running all the `fields`, aliased `u0`, `u1`, ...
+pub fn aliased_mutations(fields: &[String]) -> String {
+    let mut document = String::from("mutation {\n");
+    for (index, field) in fields.iter().enumerate() {
+        document.push_str(&format!("  u{index}: {field}\n"));
+    }
+    document.push_str("}\n");
+    document
+}
+
+/// Submits the operations as mutations of the GraphQL service of the application.
+pub async fn mutate_operations<A>(
+    application: &ApplicationWrapper<A>,
+    operations: &[FungibleOperation],
+) -> Result<()> {
+    let mutations = operations
+        .iter()
+        .map(operation_mutation)
+        .collect::<Vec<_>>();
+    application.multiple_mutate(&mutations).await?;
+    Ok(())
+}
+
+/// The balance of `owner` in the `accounts` field of the fungible and native-fungible applications.
+pub async fn query_balance<A>(
+    application: &ApplicationWrapper<A>,
+    owner: AccountOwner,
+) -> Result<Amount> {
+    let query = format!(
+        "accounts {{ entry(key: {}) {{ value }} }}",
+        owner.to_value()
+    );
+    let response = application.query(query).await?;
+    let value = &response["accounts"]["entry"]["value"];
+    if value.is_null() {
+        return Ok(Amount::ZERO);
+    }
+    Ok(serde_json::from_value(value.clone())?)
+}
+
+/// The total balance of the `accounts` field of the fungible and native-fungible applications.
+pub async fn query_total_balance<A>(application: &ApplicationWrapper<A>) -> Result<Amount> {
+    let response = application.query("accounts { entries { value } }").await?;
+    let Some(entries) = response["accounts"]["entries"].as_array() else {
+        bail!("no accounts in {response}");
+    };
+    let mut total = Amount::ZERO;
+    for entry in entries {
+        let balance: Amount = serde_json::from_value(entry["value"].clone())?;
+        total = total.saturating_add(balance);
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use linera_base::{
+        async_graphql::{
+            parser::{parse_query, types::Selection},
+            InputType, Value,
+        },
+        crypto::CryptoHash,
+        data_types::Amount,
+        identifiers::{Account, AccountOwner, ChainId},
+    };
+    use linera_sdk::abis::fungible::FungibleOperation;
+
+    use super::operation_mutation;
+
+    /// The arguments of the mutation `field`, as the GraphQL service receives them.
+    fn arguments(field: &str) -> BTreeMap<String, Value> {
+        let document = parse_query(format!("mutation {{ {field} }}")).expect("invalid mutation");
+        let (_, operation) = document.operations.iter().next().expect("no operation");
+        let [selection] = &operation.node.selection_set.node.items[..] else {
+            panic!("expected a single field in {field}");
+        };
+        let Selection::Field(field) = &selection.node else {
+            panic!("expected a field");
+        };
+        field
+            .node
+            .arguments
+            .iter()
+            .map(|(name, value)| {
+                let value = value
+                    .node
+                    .clone()
+                    .into_const()
+                    .expect("variable in the mutation");
+                (name.node.to_string(), value)
+            })
+            .collect()
+    }
+
+    fn argument<T: InputType>(arguments: &BTreeMap<String, Value>, name: &str) -> T {
+        T::parse(arguments.get(name).cloned()).unwrap_or_else(|_| panic!("invalid argument {name}"))
+    }
+
+    /// The operation that the fungible application executes for the mutation `field`.
+    fn decode(field: &str) -> FungibleOperation {
+        let (name, _) = field.split_once('(').expect("no arguments");
+        let arguments = arguments(field);
+        match name {
+            "approve" => FungibleOperation::Approve {
+                owner: argument(&arguments, "owner"),
+                spender: argument(&arguments, "spender"),
+                allowance: argument(&arguments, "allowance"),
+            },
+            "transfer" => FungibleOperation::Transfer {
+                owner: argument(&arguments, "owner"),
+                amount: argument(&arguments, "amount"),
+                target_account: argument(&arguments, "targetAccount"),
+            },
+            "transferFrom" => FungibleOperation::TransferFrom {
+                owner: argument(&arguments, "owner"),
+                spender: argument(&arguments, "spender"),
+                amount: argument(&arguments, "amount"),
+                target_account: argument(&arguments, "targetAccount"),
+            },
+            "claim" => FungibleOperation::Claim {
+                source_account: argument(&arguments, "sourceAccount"),
+                amount: argument(&arguments, "amount"),
+                target_account: argument(&arguments, "targetAccount"),
+            },
+            _ => panic!("unexpected mutation {name}"),
+        }
+    }
+
+    #[test]
+    fn test_mutations_decode_to_the_operations() {
+        let owner = AccountOwner::Address32(CryptoHash::test_hash("owner"));
+        let spender = AccountOwner::Address20([7; 20]);
+        let source_account = Account {
+            chain_id: ChainId(CryptoHash::test_hash("source")),
+            owner,
+        };
+        let target_account = Account {
+            chain_id: ChainId(CryptoHash::test_hash("target")),
+            owner: spender,
+        };
+        let amount = Amount::from_millis(1500);
+        let operations = [
+            FungibleOperation::Approve {
+                owner,
+                spender,
+                allowance: amount,
+            },
+            FungibleOperation::Transfer {
+                owner,
+                amount,
+                target_account,
+            },
+            FungibleOperation::TransferFrom {
+                owner,
+                spender,
+                amount,
+                target_account,
+            },
+            FungibleOperation::Claim {
+                source_account,
+                amount,
+                target_account,
+            },
+        ];
+        for operation in operations {
+            let mutation = operation_mutation(&operation);
+            assert_eq!(
+                serde_json::to_value(decode(&mutation)).unwrap(),
+                serde_json::to_value(&operation).unwrap(),
+                "{mutation}"
+            );
+        }
+    }
+}
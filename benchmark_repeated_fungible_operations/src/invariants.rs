@@ -0,0 +1,144 @@
+//! Checks of the final state: a fast run of a broken contract must not go unnoticed.
+//! The balances are queried before and after the transfers, since the native token
+//! balances of the owners carry over from one variant to the next on a shared local net.
+
+use std::future::Future;
+
+use anyhow::{ensure, Result};
+use fungible_no_graphql::FungibleNoGraphQlTokenAbi;
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ApplicationId, ChainId},
+};
+use linera_service::cli_wrappers::NodeService;
+
+use crate::{
+    accounts::Accounts,
+    transfers::{FungibleNoGraphql, TokenApplication},
+};
+
+impl Accounts {
+    /// The senders followed by the receivers.
+    pub fn all_accounts(&self) -> Vec<Account> {
+        self.senders
+            .iter()
+            .chain(&self.receivers)
+            .copied()
+            .collect()
+    }
+
+    /// The balances after the transfers of `amount`, from the `initial` balances of `all_accounts`.
+    pub fn expected_balances(
+        &self,
+        initial: &[(Account, Amount)],
+        amount: Amount,
+    ) -> Vec<(Account, Amount)> {
+        let mut expected = initial.to_vec();
+        for (index, sender) in self.senders.iter().enumerate() {
+            for receiver in self.transfers_of_sender(index) {
+                for (account, balance) in &mut expected {
+                    if account == sender {
+                        *balance = balance.saturating_sub(amount);
+                    } else if *account == receiver {
+                        *balance = balance.saturating_add(amount);
+                    }
+                }
+            }
+        }
+        expected
+    }
+}
+
+/// The balance of an account in the fungible-no-graphql application.
+pub async fn query_no_graphql_balance(
+    node_service: &NodeService,
+    application_id: ApplicationId<FungibleNoGraphQlTokenAbi>,
+    account: Account,
+) -> Result<Amount> {
+    let application = node_service.make_application(&account.chain_id, &application_id)?;
+    FungibleNoGraphql::balance(&application, account.owner).await
+}
+
+/// The total balance of the accounts of the fungible-no-graphql application on `chain_id`.
+pub async fn query_no_graphql_total_balance(
+    node_service: &NodeService,
+    application_id: ApplicationId<FungibleNoGraphQlTokenAbi>,
+    chain_id: ChainId,
+) -> Result<Amount> {
+    let application = node_service.make_application(&chain_id, &application_id)?;
+    FungibleNoGraphql::total_balance(&application).await
+}
+
+/// Queries the balance of every account with `balance`.
+pub async fn query_balances<F, Fut>(
+    accounts: &[Account],
+    balance: F,
+) -> Result<Vec<(Account, Amount)>>
+where
+    F: Fn(Account) -> Fut,
+    Fut: Future<Output = Result<Amount>>,
+{
+    let mut balances = Vec::new();
+    for account in accounts {
+        balances.push((*account, balance(*account).await?));
+    }
+    Ok(balances)
+}
+
+/// Compares the balances queried with `balance` with the expected ones.
+pub async fn verify_balances<F, Fut>(
+    variant: &str,
+    expected: &[(Account, Amount)],
+    balance: F,
+) -> Result<()>
+where
+    F: Fn(Account) -> Fut,
+    Fut: Future<Output = Result<Amount>>,
+{
+    let accounts = expected
+        .iter()
+        .map(|(account, _)| *account)
+        .collect::<Vec<_>>();
+    let actual = query_balances(&accounts, balance).await?;
+    let mismatches = actual
+        .iter()
+        .zip(expected)
+        .filter(|((_, actual), (_, expected))| actual != expected)
+        .map(|((account, actual), (_, expected))| {
+            format!("{account}: expected {expected}, got {actual}")
+        })
+        .collect::<Vec<_>>();
+    ensure!(
+        mismatches.is_empty(),
+        "wrong balances after {variant}:\n    {}",
+        mismatches.join("\n    ")
+    );
+    println!("Balances verified for {variant}: {} accounts", actual.len());
+    Ok(())
+}
+
+/// Checks that the accounts of the application on all the `chain_ids`, whose total per
+/// chain is queried with `total_balance`, add up to the amount `minted` by its
+/// `InitialState`, i.e. that no token was created or lost, even on an account that the
+/// benchmark does not look at.
+pub async fn verify_total_supply<F, Fut>(
+    variant: &str,
+    minted: Amount,
+    chain_ids: &[ChainId],
+    total_balance: F,
+) -> Result<()>
+where
+    F: Fn(ChainId) -> Fut,
+    Fut: Future<Output = Result<Amount>>,
+{
+    let mut total_supply = Amount::ZERO;
+    for chain_id in chain_ids {
+        total_supply = total_supply.saturating_add(total_balance(*chain_id).await?);
+    }
+    ensure!(
+        total_supply == minted,
+        "total supply not conserved by {variant}: {total_supply} instead of {minted}"
+    );
+    println!("Total supply verified for {variant}: {total_supply}");
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Submission of the transfers in groups and distribution of the latencies of the groups.
+//! Dividing the total runtime by the number of transfers hides the tail latency and the
+//! effect of the block size, hence this mode.
+
+use std::future::Future;
+
+use anyhow::Result;
+use benchmark_statistics::{latency_histogram, percentile};
+use linera_base::time::{Duration, Instant};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct Submission {
+    /// Submit the transfers of each sender in groups of this size, one group after the
+    /// other, and report the distribution of the latencies. `1` submits the transfers one
+    /// at a time. By default, all the transfers of a sender are submitted at once.
+    #[arg(long, global = true, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub block_size: Option<usize>,
+}
+
+impl Submission {
+    /// The size of the groups for a sender doing `num_transfers` transfers.
+    pub fn group_size(&self, num_transfers: usize) -> usize {
+        self.block_size.unwrap_or(num_transfers).max(1)
+    }
+
+    /// Prints the percentiles and the histogram of the latencies, in latency mode only.
+    pub fn report(&self, variant: &str, latencies: &[Duration]) {
+        let Some(block_size) = self.block_size else {
+            return;
+        };
+        let mut micros = latencies
+            .iter()
+            .map(|latency| latency.as_micros() as f64)
+            .collect::<Vec<_>>();
+        if micros.is_empty() {
+            return;
+        }
+        micros.sort_by(|a, b| a.total_cmp(b));
+        let mean = micros.iter().sum::<f64>() / micros.len() as f64;
+        println!(
+            "Latency for {variant} (block_size={block_size}, groups={}): min={:.0} p50={:.0} p90={:.0} p99={:.0} max={:.0} mean={mean:.0} micros",
+            micros.len(),
+            micros[0],
+            percentile(&micros, 50.0),
+            percentile(&micros, 90.0),
+            percentile(&micros, 99.0),
+            micros[micros.len() - 1],
+        );
+        for (bound, count) in latency_histogram(&micros) {
+            println!("    <= {bound:>9} micros: {count}");
+        }
+    }
+}
+
+/// Submits the groups of each sender one after the other, the senders running
+/// concurrently, and returns the latency of every group.
+pub async fn submit_groups<'a, A, G, F, Fut>(
+    runs: &'a [(A, Vec<G>)],
+    submit: F,
+) -> Result<Vec<Duration>>
+where
+    F: Fn(&'a A, &'a G) -> Fut,
+    Fut: Future<Output = Result<()>> + 'a,
+{
+    let submit = &submit;
+    let latencies = futures::future::try_join_all(runs.iter().map(|(sender, groups)| async move {
+        let mut latencies = Vec::new();
+        for group in groups {
+            let time = Instant::now();
+            submit(sender, group).await?;
+            latencies.push(time.elapsed());
+        }
+        Ok::<_, anyhow::Error>(latencies)
+    }))
+    .await?;
+    Ok(latencies.concat())
+}
@@ -0,0 +1,288 @@
+//! Load generator: several clients, each with its own wallet, chain and node service,
+//! transfer the fungible token at the same time. Running it for a growing number of
+//! clients shows where the local net saturates.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ChainId},
+    time::{Duration, Instant},
+    vm::VmRuntime,
+};
+use linera_sdk::abis::fungible::FungibleOperation;
+use linera_service::cli_wrappers::{
+    local_net::{get_node_port, LocalNet, ProcessInbox},
+    ApplicationWrapper, ClientWrapper, LineraNet, NodeService,
+};
+
+use crate::{
+    build_application,
+    costs::CostTracker,
+    graphql::{mutate_operations, operation_mutation, query_balance, query_total_balance},
+    invariants::{query_balances, verify_balances, verify_total_supply},
+    report::Measurement,
+    Options,
+};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct LoadGenerator {
+    /// Numbers of concurrent clients, each doing `--num-operations` transfers.
+    /// Accepts a list, e.g. `1,2,4,8`: the clients are created once and the first
+    /// ones are used for each step.
+    #[arg(long, value_delimiter = ',', default_value = "1,2,4,8", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub num_clients: Vec<usize>,
+}
+
+/// A client of the load generator, with its own wallet, chain and node service.
+struct LoadClient {
+    _client: ClientWrapper,
+    node_service: NodeService,
+    chain_id: ChainId,
+    sender: Account,
+    receiver: Account,
+}
+
+/// The transfers of one client during a step.
+struct ClientRun {
+    elapsed: Duration,
+    latencies: Vec<Duration>,
+}
+
+impl LoadGenerator {
+    async fn create_client(
+        net: &mut LocalNet,
+        main_client: &ClientWrapper,
+        main_chain_id: ChainId,
+    ) -> Result<LoadClient> {
+        let client = net.make_client().await;
+        client.wallet_init(None).await?;
+        let owner = client.keygen().await?;
+        let (chain_id, _) = main_client
+            .open_chain(main_chain_id, Some(owner), Amount::from_tokens(10))
+            .await?;
+        client.assign(owner, chain_id).await?;
+        let receiver = Account {
+            chain_id,
+            owner: client.keygen().await?,
+        };
+        let port = get_node_port().await;
+        let node_service = client.run_node_service(port, ProcessInbox::Skip).await?;
+        Ok(LoadClient {
+            _client: client,
+            node_service,
+            chain_id,
+            sender: Account { chain_id, owner },
+            receiver,
+        })
+    }
+
+    pub async fn run(
+        &self,
+        net: &mut LocalNet,
+        main_client: &ClientWrapper,
+        cost_tracker: &CostTracker,
+        options: &Options,
+    ) -> Result<Vec<Measurement>> {
+        use fungible::{FungibleTokenAbi, InitialState, Parameters};
+        let num_operations = options.fan_out.num_operations;
+        let max_clients = self.num_clients.iter().copied().max().unwrap_or(0);
+        let main_chain_id = main_client.load_wallet()?.default_chain().unwrap();
+        let main_owner = main_client.get_owner().unwrap();
+
+        let mut clients = Vec::new();
+        for _ in 0..max_clients {
+            clients.push(Self::create_client(net, main_client, main_chain_id).await?);
+        }
+
+        let (contract_path, service_path) = build_application(main_client, "fungible").await?;
+        let params = Parameters::new("NAT");
+        // Every step spends `num_operations` tokens of each of its clients.
+        let balance = Amount::from_tokens((num_operations * self.num_clients.len()) as u128);
+        let total_balance =
+            Amount::from_tokens((num_operations * self.num_clients.len() * max_clients) as u128);
+        let state = InitialState {
+            accounts: BTreeMap::from([(main_owner, total_balance)]),
+        };
+        let application_id = main_client
+            .publish_and_create::<FungibleTokenAbi, Parameters, InitialState>(
+                contract_path,
+                service_path,
+                VmRuntime::Wasm,
+                &params,
+                &state,
+                &[],
+                None,
+            )
+            .await?;
+
+        let port = get_node_port().await;
+        let mut main_node_service = main_client
+            .run_node_service(port, ProcessInbox::Skip)
+            .await?;
+        let funding = clients
+            .iter()
+            .map(|client| FungibleOperation::Transfer {
+                owner: main_owner,
+                amount: balance,
+                target_account: client.sender,
+            })
+            .collect::<Vec<_>>();
+        if !funding.is_empty() {
+            let main_application =
+                main_node_service.make_application(&main_chain_id, &application_id)?;
+            mutate_operations(&main_application, &funding).await?;
+        }
+        let mut applications = Vec::new();
+        for client in &clients {
+            client.node_service.process_inbox(&client.chain_id).await?;
+            applications.push(
+                client
+                    .node_service
+                    .make_application(&client.chain_id, &application_id)?,
+            );
+        }
+        main_node_service.ensure_is_running()?;
+        let balance = |account: Account| {
+            let client = clients
+                .iter()
+                .find(|client| client.chain_id == account.chain_id)
+                .unwrap();
+            let application = client
+                .node_service
+                .make_application(&account.chain_id, &application_id);
+            async move { query_balance(&application?, account.owner).await }
+        };
+        let all_accounts = clients
+            .iter()
+            .flat_map(|client| [client.sender, client.receiver])
+            .collect::<Vec<_>>();
+        let initial_balances = query_balances(&all_accounts, &balance).await?;
+
+        let mut measurements = Vec::new();
+        for &num_clients in &self.num_clients {
+            let clients = &clients[..num_clients];
+            let mut runs = Vec::new();
+            for client in clients {
+                let mutation = operation_mutation(&FungibleOperation::Transfer {
+                    owner: client.sender.owner,
+                    amount: Amount::ONE,
+                    target_account: client.receiver,
+                });
+                let mutations = vec![mutation; num_operations];
+                let group_size = options.submission.group_size(num_operations);
+                runs.push(
+                    mutations
+                        .chunks(group_size)
+                        .map(<[String]>::to_vec)
+                        .collect::<Vec<_>>(),
+                );
+            }
+            let chains = clients
+                .iter()
+                .map(|client| (&client.node_service, client.chain_id))
+                .collect::<Vec<_>>();
+            let before = cost_tracker.snapshot_chains(&chains).await?;
+            let time_start = Instant::now();
+            let client_runs = futures::future::try_join_all(
+                applications[..num_clients]
+                    .iter()
+                    .zip(&runs)
+                    .map(|(application, groups)| submit_client(application, groups)),
+            )
+            .await?;
+            let elapsed = time_start.elapsed();
+            let after = cost_tracker.snapshot_chains(&chains).await?;
+
+            let variant = format!("load-{num_clients}-clients");
+            let total_operations = num_operations * num_clients;
+            println!(
+                "Load with {num_clients} clients: {total_operations} transfers in {} ms, TPS={:.1}",
+                elapsed.as_millis(),
+                total_operations as f64 / elapsed.as_secs_f64()
+            );
+            for (index, client_run) in client_runs.iter().enumerate() {
+                println!(
+                    "    client {index}: {} ms, average runtime per transfer={:.3} ms, max group latency={} ms",
+                    client_run.elapsed.as_millis(),
+                    client_run.elapsed.as_secs_f64() * 1000.0 / num_operations as f64,
+                    client_run.latencies.iter().max().copied().unwrap_or_default().as_millis()
+                );
+            }
+            let latencies = client_runs
+                .into_iter()
+                .flat_map(|run| run.latencies)
+                .collect::<Vec<_>>();
+            options.submission.report(&variant, &latencies);
+            let costs = cost_tracker.costs(&before, &after);
+            println!("Costs for {variant}: {costs}");
+            measurements.push(Measurement {
+                variant,
+                num_operations: total_operations,
+                elapsed,
+                costs,
+            });
+        }
+        // Client `i` did `num_operations` transfers in every step with more than `i` clients.
+        let expected = initial_balances
+            .iter()
+            .map(|(account, initial)| {
+                let index = clients
+                    .iter()
+                    .position(|client| client.chain_id == account.chain_id)
+                    .unwrap();
+                let num_steps = self
+                    .num_clients
+                    .iter()
+                    .filter(|&&num_clients| num_clients > index)
+                    .count();
+                let transferred = Amount::from_tokens((num_steps * num_operations) as u128);
+                if *account == clients[index].sender {
+                    (*account, initial.saturating_sub(transferred))
+                } else {
+                    (*account, initial.saturating_add(transferred))
+                }
+            })
+            .collect::<Vec<_>>();
+        verify_balances("load", &expected, &balance).await?;
+        let chain_total_balance = |chain_id: ChainId| {
+            let node_service = if chain_id == main_chain_id {
+                &main_node_service
+            } else {
+                &clients
+                    .iter()
+                    .find(|client| client.chain_id == chain_id)
+                    .unwrap()
+                    .node_service
+            };
+            let application = node_service.make_application(&chain_id, &application_id);
+            async move { query_total_balance(&application?).await }
+        };
+        let chain_ids = std::iter::once(main_chain_id)
+            .chain(clients.iter().map(|client| client.chain_id))
+            .collect::<Vec<_>>();
+        verify_total_supply("load", total_balance, &chain_ids, chain_total_balance).await?;
+        for client in &mut clients {
+            client.node_service.ensure_is_running()?;
+        }
+        Ok(measurements)
+    }
+}
+
+async fn submit_client(
+    application: &ApplicationWrapper<fungible::FungibleTokenAbi>,
+    groups: &[Vec<String>],
+) -> Result<ClientRun> {
+    let mut latencies = Vec::new();
+    let time_start = Instant::now();
+    for group in groups {
+        let time = Instant::now();
+        application.multiple_mutate(group).await?;
+        latencies.push(time.elapsed());
+    }
+    Ok(ClientRun {
+        elapsed: time_start.elapsed(),
+        latencies,
+    })
+}
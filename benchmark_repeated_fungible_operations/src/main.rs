@@ -1,314 +1,218 @@
+mod accounts;
+mod costs;
+mod graphql;
+mod invariants;
+mod latency;
+mod load;
+mod report;
+mod topology;
+mod transfers;
+mod workflows;
+
 use anyhow::Result;
-use linera_base::{
-    data_types::Amount,
-    identifiers::{Account, AccountOwner},
-    vm::VmRuntime,
-    time::Instant,
-};
-use linera_service::cli_wrappers::{
-    local_net::{get_node_port, LocalNetConfig, ProcessInbox, Database},
-    LineraNet, LineraNetConfig, Network,
+use clap::{
+    error::ErrorKind, parser::ValueSource, CommandFactory, FromArgMatches, Parser, Subcommand,
 };
 use linera_service::cli_wrappers::ClientWrapper;
+use linera_service::cli_wrappers::{local_net::LocalNet, LineraNet, LineraNetConfig};
+use std::env;
 use std::path::PathBuf;
-use linera_base::async_graphql::InputType;
-//use linera_base::async_graphql::ScalarType;
-use std::{collections::BTreeMap, env};
 
-fn get_config() -> LocalNetConfig {
-    let mut config = LocalNetConfig::new_test(Database::Service, Network::Grpc);
-    config.num_initial_validators = 4;
-    config.num_shards = 4;
-    config
-}
+use crate::{
+    accounts::FanOut,
+    costs::CostTracker,
+    latency::Submission,
+    load::LoadGenerator,
+    report::{print_comparison, print_scaling, Measurement, TopologyRun},
+    topology::Topology,
+    transfers::{
+        end_to_end_repeated_native_transfer, end_to_end_repeated_transfer, Fungible,
+        FungibleNoGraphql, NativeFungible,
+    },
+    workflows::{end_to_end_repeated_claim, end_to_end_repeated_transfer_from},
+};
 
-async fn build_application(client: &ClientWrapper, name: &str) -> Result<(PathBuf, PathBuf)> {
-    let path = env::current_dir()?.join("./smart_contract_code/").join(name);
-    Ok(client.build_application(&path, name, true).await?)
+#[derive(Parser, Debug)]
+#[command(about = "Repeated transfers with the fungible applications and the native token")]
+struct Cli {
+    #[command(flatten)]
+    topology: Topology,
+    #[command(flatten)]
+    options: Options,
+    #[command(subcommand)]
+    command: Command,
 }
 
-async fn end_to_end_repeated_transfer_fungible() -> Result<()> {
-    let num_operations = 500;
-    use fungible::{FungibleTokenAbi, InitialState, Parameters};
-    let config = get_config();
-
-    tracing::info!("Starting repeated transfer in fungible");
-    let (mut net, client) = config.instantiate().await?;
-
-    let chain_id = client.load_wallet()?.default_chain().unwrap();
-
-    let account_owner1 = client.get_owner().unwrap();
-    let account_owner2 = client.keygen().await?;
-
-
-    let (contract_path, service_path) = build_application(&client, "fungible").await?;
-
-
-    let params = Parameters::new("NAT");
-    let accounts = BTreeMap::from([
-        (account_owner1, Amount::from_tokens(1000)),
-    ]);
-    let state = InitialState { accounts };
-    let application_id = client
-        .publish_and_create::<FungibleTokenAbi, Parameters, InitialState>(
-            contract_path,
-            service_path,
-            VmRuntime::Wasm,
-            &params,
-            &state,
-            &[],
-            None,
-        )
-        .await?;
-
-    let port = get_node_port().await;
-    let mut node_service = client.run_node_service(port, ProcessInbox::Skip).await?;
-    let app_id = node_service.make_application(&chain_id, &application_id)?;
-
-    let amount_transfer = Amount::ONE;
-    let destination = Account {
-        chain_id,
-        owner: account_owner2,
-    };
-    let mutation = format!(
-        "transfer(owner: {}, amount: \"{}\", targetAccount: {})",
-        account_owner1.to_value(),
-        amount_transfer,
-        destination.to_value(),
-    );
-    let mutations = vec![mutation; num_operations];
-    let time_start = Instant::now();
-    app_id.multiple_mutate(&mutations).await?;
-    let average_time = (time_start.elapsed().as_millis() as f64) / (num_operations as f64);
-    println!("Average runtime for fungible transfer={average_time}");
-
-    node_service.ensure_is_running()?;
-    net.ensure_is_running().await?;
-    net.terminate().await?;
-    println!("Successful end");
-    Ok(())
+/// The options shared by all the benchmarks.
+#[derive(clap::Args, Debug)]
+struct Options {
+    #[command(flatten)]
+    fan_out: FanOut,
+    #[command(flatten)]
+    submission: Submission,
 }
 
-
-async fn end_to_end_repeated_transfer_fungible_no_graphql() -> Result<()> {
-    let num_operations = 500;
-    use fungible_no_graphql::{FungibleNoGraphQlTokenAbi, FungibleOperation, FungibleNoGraphQlRequest};
-    use fungible::{InitialState, Parameters};
-    let config = get_config();
-
-    tracing::info!("Starting repeated transfer in fungible");
-    let (mut net, client) = config.instantiate().await?;
-
-    let chain_id = client.load_wallet()?.default_chain().unwrap();
-
-    let account_owner1 = client.get_owner().unwrap();
-    let account_owner2 = client.keygen().await?;
-
-
-    let (contract_path, service_path) = build_application(&client, "fungible-no-graphql").await?;
-
-
-    let params = Parameters::new("NAT");
-    let accounts = BTreeMap::from([
-        (account_owner1, Amount::from_tokens(1000)),
-    ]);
-    let state = InitialState { accounts };
-    let application_id = client
-        .publish_and_create::<FungibleNoGraphQlTokenAbi, Parameters, InitialState>(
-            contract_path,
-            service_path,
-            VmRuntime::Wasm,
-            &params,
-            &state,
-            &[],
-            None,
-        )
-        .await?;
-
-    let port = get_node_port().await;
-    let mut node_service = client.run_node_service(port, ProcessInbox::Skip).await?;
-    let app_id = node_service.make_application(&chain_id, &application_id)?;
-
-    let amount = Amount::ONE;
-    let target_account = Account {
-        chain_id,
-        owner: account_owner2,
-    };
-    let mut operations = Vec::new();
-    for _ in 0..num_operations {
-        operations.push(FungibleOperation::Transfer {
-            owner: account_owner1,
-            amount,
-            target_account,
-        });
-    }
-    let query = FungibleNoGraphQlRequest::Operations { operations };
-
-    let time_start = Instant::now();
-    app_id.run_json_query(&query).await?;
-    let average_time = (time_start.elapsed().as_millis() as f64) / (num_operations as f64);
-    println!("Average runtime for fungible-no-graphql transfer={average_time}");
-
-    node_service.ensure_is_running()?;
-    net.ensure_is_running().await?;
-    net.terminate().await?;
-    println!("Successful end");
-    Ok(())
+#[derive(Subcommand, Clone, Debug)]
+enum Command {
+    /// Transfers with the GraphQL mutations of the fungible application.
+    RepeatedFungible,
+    /// Transfers with the JSON requests of the fungible-no-graphql application.
+    RepeatedFungibleNoGraphql,
+    /// Transfers of the native token through the node service.
+    RepeatedNativeTransfer,
+    /// Transfers with the GraphQL mutations of the native-fungible application.
+    RepeatedNativeFungible,
+    /// `Approve` operations signed by an owner, then `TransferFrom` operations signed by
+    /// the spender, with the fungible-no-graphql application.
+    RepeatedTransferFrom,
+    /// `Claim` operations pulling tokens from the default chain, with the fungible-no-graphql application.
+    RepeatedClaim,
+    /// The four transfer benchmarks, one after the other on the same local net, followed by a comparison table.
+    All,
+    /// Concurrent clients, each with its own wallet, chain and node service, doing
+    /// transfers with the fungible application. Of the fan-out options, only
+    /// `--num-operations` applies.
+    Load(LoadGenerator),
 }
 
-async fn end_to_end_repeated_transfer_native_fungible() -> Result<()> {
-    let num_operations = 500;
-    use fungible::{NativeFungibleTokenAbi, InitialState, Parameters};
-    let config = get_config();
-
-    tracing::info!("Starting repeated transfer in fungible");
-    let (mut net, client) = config.instantiate().await?;
-
-    let chain_id = client.load_wallet()?.default_chain().unwrap();
-
-    let account_owner1 = client.get_owner().unwrap();
-    let account_owner2 = client.keygen().await?;
-
-
-    let (contract_path, service_path) = build_application(&client, "native-fungible").await?;
-
-
-    let params = Parameters::new("NAT");
-    let accounts = BTreeMap::from([
-        (account_owner1, Amount::from_tokens(1000)),
-    ]);
-    let state = InitialState { accounts };
-    let application_id = client
-        .publish_and_create::<NativeFungibleTokenAbi, Parameters, InitialState>(
-            contract_path,
-            service_path,
-            VmRuntime::Wasm,
-            &params,
-            &state,
-            &[],
-            None,
-        )
-        .await?;
-
-    let port = get_node_port().await;
-    let mut node_service = client.run_node_service(port, ProcessInbox::Skip).await?;
-    let app_id = node_service.make_application(&chain_id, &application_id)?;
+impl Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Command::RepeatedFungible => "repeated-fungible",
+            Command::RepeatedFungibleNoGraphql => "repeated-fungible-no-graphql",
+            Command::RepeatedNativeTransfer => "repeated-native-transfer",
+            Command::RepeatedNativeFungible => "repeated-native-fungible",
+            Command::RepeatedTransferFrom => "repeated-transfer-from",
+            Command::RepeatedClaim => "repeated-claim",
+            Command::All => "all",
+            Command::Load(_) => "load",
+        }
+    }
 
-    let amount_transfer = Amount::ONE;
-    let destination = Account {
-        chain_id,
-        owner: account_owner2,
-    };
-    let mutation = format!(
-        "transfer(owner: {}, amount: \"{}\", targetAccount: {})",
-        account_owner1.to_value(),
-        amount_transfer,
-        destination.to_value(),
-    );
-    let mutations = vec![mutation; num_operations];
-    let time_start = Instant::now();
-    app_id.multiple_mutate(&mutations).await?;
-    let average_time = (time_start.elapsed().as_millis() as f64) / (num_operations as f64);
-    println!("Average runtime for native-fungible transfer={average_time}");
+    /// The benchmarks run by the command.
+    fn benchmarks(&self) -> Vec<Command> {
+        match self {
+            Command::All => vec![
+                Command::RepeatedFungible,
+                Command::RepeatedFungibleNoGraphql,
+                Command::RepeatedNativeTransfer,
+                Command::RepeatedNativeFungible,
+            ],
+            command => vec![command.clone()],
+        }
+    }
 
-    node_service.ensure_is_running()?;
-    net.ensure_is_running().await?;
-    net.terminate().await?;
-    println!("Successful end for repeated-native-fungible");
-    Ok(())
+    async fn run(
+        &self,
+        net: &mut LocalNet,
+        client: &ClientWrapper,
+        cost_tracker: &CostTracker,
+        options: &Options,
+    ) -> Result<Vec<Measurement>> {
+        let measurement = match self {
+            Command::RepeatedFungible => {
+                end_to_end_repeated_transfer::<Fungible>(client, cost_tracker, options).await?
+            }
+            Command::RepeatedFungibleNoGraphql => {
+                end_to_end_repeated_transfer::<FungibleNoGraphql>(client, cost_tracker, options)
+                    .await?
+            }
+            Command::RepeatedNativeTransfer => {
+                end_to_end_repeated_native_transfer(client, cost_tracker, options).await?
+            }
+            Command::RepeatedNativeFungible => {
+                end_to_end_repeated_transfer::<NativeFungible>(client, cost_tracker, options)
+                    .await?
+            }
+            Command::RepeatedClaim => {
+                end_to_end_repeated_claim(client, cost_tracker, options).await?
+            }
+            Command::RepeatedTransferFrom => {
+                return end_to_end_repeated_transfer_from(net, client, cost_tracker, options).await
+            }
+            Command::Load(load_generator) => {
+                return load_generator.run(net, client, cost_tracker, options).await
+            }
+            Command::All => unreachable!("`all` is expanded by `benchmarks`"),
+        };
+        Ok(vec![measurement])
+    }
 }
 
-async fn end_to_end_repeated_native_transfer() -> Result<()> {
-    let num_operations = 500;
-    let config = get_config();
-
-    tracing::info!("Starting repeated transfer using node_service");
-    let (mut net, client) = config.instantiate().await?;
-
-    let chain_id = client.load_wallet()?.default_chain().unwrap();
-
-    let account_owner1 = client.get_owner().unwrap();
-    let account_owner2 = client.keygen().await?;
-
-
-    let port = get_node_port().await;
-    let mut node_service = client.run_node_service(port, ProcessInbox::Skip).await?;
-
-    node_service
-        .transfer(
-            chain_id,
-            AccountOwner::CHAIN,
-            Account { chain_id, owner: account_owner1 },
-            Amount::from_tokens(num_operations + 10),
-        )
-        .await?;
-
-    let recipient = Account {
-        chain_id,
-        owner: account_owner2,
-    };
-    let amount = Amount::ONE;
-
-    let mut query = String::from("mutation {\n");
-    let op = format!("transfer(chainId: \"{chain_id}\", owner: {}, recipient: {}, amount: \"{amount}\")",
-                     account_owner1.to_value(), recipient.to_value());
-    for index in 0..num_operations {
-        query = format!("{}  u{}: {}\n", query, index, op);
+/// The fan-out options that `load` does not use: each of its clients is one sender with
+/// one receiver on its own chain, funded with what its transfers need.
+const FAN_OUT_OPTIONS_IGNORED_BY_LOAD: [&str; 5] = [
+    "num_senders",
+    "num_receivers",
+    "initial_balance",
+    "balance_distribution",
+    "cross_chain",
+];
+
+/// Parses the command line, rejecting the options that the subcommand would ignore.
+fn parse_cli() -> Cli {
+    let matches = Cli::command().get_matches();
+    if let Some(("load", load_matches)) = matches.subcommand() {
+        for id in FAN_OUT_OPTIONS_IGNORED_BY_LOAD {
+            let is_given = [&matches, load_matches]
+                .iter()
+                .any(|matches| matches.value_source(id) == Some(ValueSource::CommandLine));
+            if is_given {
+                let message = format!(
+                    "--{} is not supported by the load subcommand",
+                    id.replace('_', "-")
+                );
+                Cli::command()
+                    .error(ErrorKind::ArgumentConflict, message)
+                    .exit();
+            }
+        }
     }
-    query.push_str("}\n");
-
-    let time_start = Instant::now();
-    let _data = node_service.query_node(&query).await?;
-    let average_time = (time_start.elapsed().as_millis() as f64) / (num_operations as f64);
-    println!("Average runtime for node-service transfer={average_time}");
-
-    node_service.ensure_is_running()?;
-    net.ensure_is_running().await?;
-    net.terminate().await?;
-    println!("Successful end for repeated-native-transfer");
-//    println!("data={data}");
-    Ok(())
+    Cli::from_arg_matches(&matches).unwrap_or_else(|error| error.exit())
 }
 
-
-
-
+async fn build_application(client: &ClientWrapper, name: &str) -> Result<(PathBuf, PathBuf)> {
+    let path = env::current_dir()?
+        .join("./smart_contract_code/")
+        .join(name);
+    Ok(client.build_application(&path, name, true).await?)
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() < 2 {
-        eprintln!("Error: No test specified");
-        eprintln!("Usage: {} <test-name>", args[0]);
-        eprintln!("Available tests: repeated-fungible, repeated-fungible-no-graphql, all");
-        std::process::exit(1);
-    }
-
-    let test_name = &args[1];
-
-    match test_name.as_str() {
-        "repeated-fungible" => {
-            println!("Running repeated-fungible test...");
-            end_to_end_repeated_transfer_fungible().await?;
-        }
-        "repeated-fungible-no-graphql" => {
-            println!("Running repeated-fungible-no-graphql test...");
-            end_to_end_repeated_transfer_fungible_no_graphql().await?;
-        }
-        "repeated-native-transfer" => {
-            println!("Running repeated-native-transfer test...");
-            end_to_end_repeated_native_transfer().await?;
-        }
-        "repeated-native-fungible" => {
-            println!("Running repeated-native-fungible test...");
-            end_to_end_repeated_transfer_native_fungible().await?;
+    let cli = parse_cli();
+    let options = &cli.options;
+
+    let mut runs = Vec::new();
+    for (num_validators, num_shards) in cli.topology.sizes() {
+        println!("Local net with {num_validators} validators and {num_shards} shards");
+        let config = cli.topology.config(num_validators, num_shards);
+        let cost_tracker = CostTracker::for_local_net(&config);
+        let (mut net, client) = config.instantiate().await?;
+        let mut measurements = Vec::new();
+        for benchmark in cli.command.benchmarks() {
+            println!("Running {} test...", benchmark.name());
+            measurements.extend(
+                benchmark
+                    .run(&mut net, &client, &cost_tracker, options)
+                    .await?,
+            );
+            println!("Successful end for {}", benchmark.name());
         }
-        _ => {
-            eprintln!("Error: Unknown test '{}'", test_name);
-            std::process::exit(1);
+        net.ensure_is_running().await?;
+        net.terminate().await?;
+
+        if measurements.len() > 1 {
+            print_comparison(&measurements);
         }
+        runs.push(TopologyRun {
+            num_validators,
+            num_shards,
+            measurements,
+        });
+    }
+    if runs.len() > 1 {
+        print_scaling(&runs);
     }
-
     Ok(())
 }
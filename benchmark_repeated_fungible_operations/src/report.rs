@@ -0,0 +1,91 @@
+use linera_base::time::Duration;
+
+use crate::costs::Costs;
+
+/// The measured runtime of the transfers of one benchmark variant.
+pub struct Measurement {
+    pub variant: String,
+    pub num_operations: usize,
+    pub elapsed: Duration,
+    pub costs: Costs,
+}
+
+impl Measurement {
+    /// Average runtime per transfer, in milliseconds.
+    pub fn average_time(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1000.0 / self.num_operations as f64
+    }
+
+    pub fn throughput(&self) -> f64 {
+        self.num_operations as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Prints the variants side by side. The `overhead` column is the average runtime
+/// relative to the fastest variant. The fuel is per validator, when the validators expose it.
+pub fn print_comparison(measurements: &[Measurement]) {
+    let best = measurements
+        .iter()
+        .map(Measurement::average_time)
+        .fold(f64::INFINITY, f64::min);
+    println!(
+        "{:<36} {:>10} {:>12} {:>12} {:>12} {:>9} {:>16} {:>14}",
+        "variant", "operations", "total_ms", "ms_per_op", "ops_per_sec", "overhead", "fees", "fuel"
+    );
+    for measurement in measurements {
+        let fuel = measurement
+            .costs
+            .metrics
+            .map(|metrics| format!("{:.0}", metrics.fuel))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<36} {:>10} {:>12.0} {:>12.3} {:>12.1} {:>8.2}x {:>16} {:>14}",
+            measurement.variant,
+            measurement.num_operations,
+            measurement.elapsed.as_secs_f64() * 1000.0,
+            measurement.average_time(),
+            measurement.throughput(),
+            measurement.average_time() / best,
+            measurement.costs.fees.to_string(),
+            fuel
+        );
+    }
+}
+
+/// The measurements of the benchmarks on one local net.
+pub struct TopologyRun {
+    pub num_validators: usize,
+    pub num_shards: usize,
+    pub measurements: Vec<Measurement>,
+}
+
+/// Prints the throughput of every variant for every topology. The `speedup` column is the
+/// throughput relative to the one of the first topology.
+pub fn print_scaling(runs: &[TopologyRun]) {
+    println!(
+        "{:<36} {:>10} {:>10} {:>12} {:>9}",
+        "variant", "validators", "shards", "ops_per_sec", "speedup"
+    );
+    let Some(first) = runs.first() else {
+        return;
+    };
+    for reference in &first.measurements {
+        for run in runs {
+            let Some(measurement) = run
+                .measurements
+                .iter()
+                .find(|measurement| measurement.variant == reference.variant)
+            else {
+                continue;
+            };
+            println!(
+                "{:<36} {:>10} {:>10} {:>12.1} {:>8.2}x",
+                measurement.variant,
+                run.num_validators,
+                run.num_shards,
+                measurement.throughput(),
+                measurement.throughput() / reference.throughput()
+            );
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use clap::ValueEnum;
+use linera_service::cli_wrappers::{
+    local_net::{Database, LocalNetConfig},
+    Network,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum NetworkKind {
+    Grpc,
+    Tcp,
+    Udp,
+}
+
+/// The storage of the validators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DatabaseKind {
+    /// The storage service, reached through `LINERA_STORAGE_SERVICE`.
+    Service,
+    /// A local RocksDB directory per validator.
+    #[value(name = "rocksdb")]
+    RocksDb,
+    #[value(name = "scylladb")]
+    ScyllaDb,
+    #[value(name = "dynamodb")]
+    DynamoDb,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct Topology {
+    /// Numbers of validators of the local net. Accepts a list, e.g. `1,4,7`: the
+    /// benchmarks are then run on one local net per number of validators and shards.
+    #[arg(long, global = true, value_delimiter = ',', default_value = "4", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub num_validators: Vec<usize>,
+
+    /// Numbers of shards per validator. Accepts a list, e.g. `1,2,4`.
+    #[arg(long, global = true, value_delimiter = ',', default_value = "4", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub num_shards: Vec<usize>,
+
+    #[arg(long, global = true, value_enum, default_value_t = NetworkKind::Grpc)]
+    pub network: NetworkKind,
+
+    #[arg(long, global = true, value_enum, default_value_t = DatabaseKind::Service)]
+    pub database: DatabaseKind,
+}
+
+impl Topology {
+    /// The pairs `(num_validators, num_shards)` to run the benchmarks on.
+    pub fn sizes(&self) -> Vec<(usize, usize)> {
+        self.num_validators
+            .iter()
+            .flat_map(|&num_validators| {
+                self.num_shards
+                    .iter()
+                    .map(move |&num_shards| (num_validators, num_shards))
+            })
+            .collect()
+    }
+
+    pub fn config(&self, num_validators: usize, num_shards: usize) -> LocalNetConfig {
+        let database = match self.database {
+            DatabaseKind::Service => Database::Service,
+            DatabaseKind::RocksDb => Database::RocksDb,
+            DatabaseKind::ScyllaDb => Database::ScyllaDb,
+            DatabaseKind::DynamoDb => Database::DynamoDb,
+        };
+        let network = match self.network {
+            NetworkKind::Grpc => Network::Grpc,
+            NetworkKind::Tcp => Network::Tcp,
+            NetworkKind::Udp => Network::Udp,
+        };
+        let mut config = LocalNetConfig::new_test(database, network);
+        config.num_initial_validators = num_validators;
+        config.num_shards = num_shards;
+        config
+    }
+}
@@ -0,0 +1,401 @@
+//! The transfer benchmarks. The fungible, native-fungible and fungible-no-graphql
+//! applications only differ in how their operations are submitted and their balances
+//! queried, so they share one benchmark, generic over `TokenApplication`. The native
+//! transfers of the node service share the measured part with it.
+
+use std::{collections::BTreeMap, future::Future};
+
+use anyhow::Result;
+use fungible_no_graphql::{FungibleNoGraphQlRequest, FungibleNoGraphQlTokenAbi};
+use linera_base::{
+    abi::{ContractAbi, ServiceAbi},
+    data_types::Amount,
+    identifiers::{Account, AccountOwner},
+    time::Instant,
+    vm::VmRuntime,
+};
+use linera_sdk::abis::fungible::FungibleOperation;
+use linera_service::cli_wrappers::{
+    local_net::{get_node_port, ProcessInbox},
+    ApplicationWrapper, ClientWrapper, NodeService,
+};
+
+use crate::{
+    accounts::Accounts,
+    build_application,
+    costs::CostTracker,
+    graphql::{
+        aliased_mutations, native_transfer_mutation, operation_mutation, query_balance,
+        query_total_balance,
+    },
+    invariants::{query_balances, verify_balances, verify_total_supply},
+    latency::submit_groups,
+    report::Measurement,
+    Options,
+};
+
+/// A token application of the benchmarks and the way its operations are submitted.
+pub trait TokenApplication {
+    /// The name of the variant, which is also the directory of the application in
+    /// `smart_contract_code`.
+    const NAME: &'static str;
+    /// Whether the accounts only hold the tokens minted by the `InitialState` of the
+    /// application. The native-fungible accounts hold the native token, which also pays
+    /// the fees and is transferred by the other benchmarks.
+    const MINTED_ONLY: bool;
+    type Abi: ContractAbi + ServiceAbi;
+    /// Operations submitted at once.
+    type Group;
+
+    fn group(operations: Vec<FungibleOperation>) -> Self::Group;
+
+    async fn submit(application: &ApplicationWrapper<Self::Abi>, group: &Self::Group)
+        -> Result<()>;
+
+    async fn balance(
+        application: &ApplicationWrapper<Self::Abi>,
+        owner: AccountOwner,
+    ) -> Result<Amount>;
+
+    /// The total balance of the accounts on the chain of `application`.
+    async fn total_balance(application: &ApplicationWrapper<Self::Abi>) -> Result<Amount>;
+}
+
+/// The fungible application, with GraphQL mutations.
+pub struct Fungible;
+
+/// The native-fungible application, with GraphQL mutations.
+pub struct NativeFungible;
+
+/// The fungible-no-graphql application, with JSON requests.
+pub struct FungibleNoGraphql;
+
+impl TokenApplication for Fungible {
+    const NAME: &'static str = "fungible";
+    const MINTED_ONLY: bool = true;
+    type Abi = fungible::FungibleTokenAbi;
+    type Group = Vec<String>;
+
+    fn group(operations: Vec<FungibleOperation>) -> Vec<String> {
+        operations.iter().map(operation_mutation).collect()
+    }
+
+    async fn submit(
+        application: &ApplicationWrapper<Self::Abi>,
+        mutations: &Vec<String>,
+    ) -> Result<()> {
+        application.multiple_mutate(mutations).await?;
+        Ok(())
+    }
+
+    async fn balance(
+        application: &ApplicationWrapper<Self::Abi>,
+        owner: AccountOwner,
+    ) -> Result<Amount> {
+        query_balance(application, owner).await
+    }
+
+    async fn total_balance(application: &ApplicationWrapper<Self::Abi>) -> Result<Amount> {
+        query_total_balance(application).await
+    }
+}
+
+impl TokenApplication for NativeFungible {
+    const NAME: &'static str = "native-fungible";
+    const MINTED_ONLY: bool = false;
+    type Abi = fungible::NativeFungibleTokenAbi;
+    type Group = Vec<String>;
+
+    fn group(operations: Vec<FungibleOperation>) -> Vec<String> {
+        operations.iter().map(operation_mutation).collect()
+    }
+
+    async fn submit(
+        application: &ApplicationWrapper<Self::Abi>,
+        mutations: &Vec<String>,
+    ) -> Result<()> {
+        application.multiple_mutate(mutations).await?;
+        Ok(())
+    }
+
+    async fn balance(
+        application: &ApplicationWrapper<Self::Abi>,
+        owner: AccountOwner,
+    ) -> Result<Amount> {
+        query_balance(application, owner).await
+    }
+
+    async fn total_balance(application: &ApplicationWrapper<Self::Abi>) -> Result<Amount> {
+        query_total_balance(application).await
+    }
+}
+
+impl TokenApplication for FungibleNoGraphql {
+    const NAME: &'static str = "fungible-no-graphql";
+    const MINTED_ONLY: bool = true;
+    type Abi = FungibleNoGraphQlTokenAbi;
+    type Group = FungibleNoGraphQlRequest;
+
+    fn group(operations: Vec<FungibleOperation>) -> FungibleNoGraphQlRequest {
+        FungibleNoGraphQlRequest::Operations { operations }
+    }
+
+    async fn submit(
+        application: &ApplicationWrapper<Self::Abi>,
+        request: &FungibleNoGraphQlRequest,
+    ) -> Result<()> {
+        application.run_json_query(request).await?;
+        Ok(())
+    }
+
+    async fn balance(
+        application: &ApplicationWrapper<Self::Abi>,
+        owner: AccountOwner,
+    ) -> Result<Amount> {
+        let value = application
+            .run_json_query(&FungibleNoGraphQlRequest::Balance { owner })
+            .await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    async fn total_balance(application: &ApplicationWrapper<Self::Abi>) -> Result<Amount> {
+        let value = application
+            .run_json_query(&FungibleNoGraphQlRequest::TotalBalance)
+            .await?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// Transfers between the accounts of `--num-senders` and `--num-receivers` with the
+/// application `T`.
+pub async fn end_to_end_repeated_transfer<T: TokenApplication>(
+    client: &ClientWrapper,
+    cost_tracker: &CostTracker,
+    options: &Options,
+) -> Result<Measurement> {
+    use fungible::{InitialState, Parameters};
+    tracing::info!("Starting repeated transfer in {}", T::NAME);
+
+    let accounts = options.fan_out.create_accounts(client).await?;
+    let account_owner1 = accounts.senders[0].owner;
+
+    let (contract_path, service_path) = build_application(client, T::NAME).await?;
+    let params = Parameters::new("NAT");
+    let state = InitialState {
+        accounts: BTreeMap::from([(account_owner1, accounts.total_balance())]),
+    };
+    let application_id = client
+        .publish_and_create::<T::Abi, Parameters, InitialState>(
+            contract_path,
+            service_path,
+            VmRuntime::Wasm,
+            &params,
+            &state,
+            &[],
+            None,
+        )
+        .await?;
+
+    let port = get_node_port().await;
+    let mut node_service = client.run_node_service(port, ProcessInbox::Skip).await?;
+
+    let funding = accounts
+        .remote_senders()
+        .map(|(sender, balance)| FungibleOperation::Transfer {
+            owner: account_owner1,
+            amount: balance,
+            target_account: *sender,
+        })
+        .collect::<Vec<_>>();
+    if !funding.is_empty() {
+        let application = node_service.make_application(&accounts.chain_id, &application_id)?;
+        T::submit(&application, &T::group(funding)).await?;
+        accounts.process_sender_inboxes(&node_service).await?;
+    }
+
+    let amount = Amount::ONE;
+    let mut runs = Vec::new();
+    for (index, sender) in accounts.senders.iter().enumerate() {
+        let recipients = accounts.transfers_of_sender(index);
+        if recipients.is_empty() {
+            continue;
+        }
+        let group_size = options.submission.group_size(recipients.len());
+        let groups = recipients
+            .chunks(group_size)
+            .map(|chunk| {
+                let operations = chunk
+                    .iter()
+                    .map(|target_account| FungibleOperation::Transfer {
+                        owner: sender.owner,
+                        amount,
+                        target_account: *target_account,
+                    })
+                    .collect();
+                T::group(operations)
+            })
+            .collect::<Vec<_>>();
+        runs.push((
+            node_service.make_application(&sender.chain_id, &application_id)?,
+            groups,
+        ));
+    }
+
+    let node_service_ref = &node_service;
+    let balance = |account: Account| {
+        let application = node_service_ref.make_application(&account.chain_id, &application_id);
+        async move { T::balance(&application?, account.owner).await }
+    };
+    let measurement = measure_transfers(
+        format!("{}{}", T::NAME, accounts.label()),
+        &accounts,
+        &node_service,
+        cost_tracker,
+        options,
+        amount,
+        &runs,
+        |application, group| T::submit(application, group),
+        balance,
+    )
+    .await?;
+    if T::MINTED_ONLY {
+        let total_balance = |chain_id| {
+            let application = node_service_ref.make_application(&chain_id, &application_id);
+            async move { T::total_balance(&application?).await }
+        };
+        verify_total_supply(
+            &measurement.variant,
+            accounts.total_balance(),
+            &accounts.chain_ids(),
+            total_balance,
+        )
+        .await?;
+    }
+    node_service.ensure_is_running()?;
+    Ok(measurement)
+}
+
+/// Transfers of the native token with the `transfer` mutation of the node service.
+pub async fn end_to_end_repeated_native_transfer(
+    client: &ClientWrapper,
+    cost_tracker: &CostTracker,
+    options: &Options,
+) -> Result<Measurement> {
+    tracing::info!("Starting repeated transfer using node_service");
+
+    let accounts = options.fan_out.create_accounts(client).await?;
+    let chain_id = accounts.chain_id;
+
+    let port = get_node_port().await;
+    let mut node_service = client.run_node_service(port, ProcessInbox::Skip).await?;
+
+    for (sender, balance) in accounts
+        .senders
+        .iter()
+        .zip(accounts.balances.iter().copied())
+    {
+        node_service
+            .transfer(
+                chain_id,
+                AccountOwner::CHAIN,
+                *sender,
+                balance.saturating_add(Amount::from_tokens(10)),
+            )
+            .await?;
+    }
+    accounts.process_sender_inboxes(&node_service).await?;
+
+    let amount = Amount::ONE;
+    let mut runs = Vec::new();
+    for (index, sender) in accounts.senders.iter().enumerate() {
+        let recipients = accounts.transfers_of_sender(index);
+        if recipients.is_empty() {
+            continue;
+        }
+        let group_size = options.submission.group_size(recipients.len());
+        let mut queries = Vec::new();
+        for chunk in recipients.chunks(group_size) {
+            let fields = chunk
+                .iter()
+                .map(|recipient| {
+                    native_transfer_mutation(sender.chain_id, sender.owner, recipient, amount)
+                })
+                .collect::<Vec<_>>();
+            queries.push(aliased_mutations(&fields));
+        }
+        runs.push((sender.chain_id, queries));
+    }
+
+    let node_service_ref = &node_service;
+    let measurement = measure_transfers(
+        format!("node-service{}", accounts.label()),
+        &accounts,
+        &node_service,
+        cost_tracker,
+        options,
+        amount,
+        &runs,
+        |_chain_id, query| async move {
+            let _data = node_service_ref.query_node(query).await?;
+            Ok(())
+        },
+        |account: Account| async move { node_service_ref.balance(&account).await },
+    )
+    .await?;
+    node_service.ensure_is_running()?;
+    Ok(measurement)
+}
+
+/// Submits the groups of `runs` with `submit` and measures the time until the receivers
+/// are credited, i.e. including the processing of their inbox with `--cross-chain`.
+/// Then reports the latencies and the costs, and checks the balances with `balance`.
+#[expect(clippy::too_many_arguments)]
+async fn measure_transfers<'a, S, G, F, Fut, B, BFut>(
+    variant: String,
+    accounts: &Accounts,
+    node_service: &NodeService,
+    cost_tracker: &CostTracker,
+    options: &Options,
+    amount: Amount,
+    runs: &'a [(S, Vec<G>)],
+    submit: F,
+    balance: B,
+) -> Result<Measurement>
+where
+    F: Fn(&'a S, &'a G) -> Fut,
+    Fut: Future<Output = Result<()>> + 'a,
+    B: Fn(Account) -> BFut,
+    BFut: Future<Output = Result<Amount>>,
+{
+    let cross_chain = options.fan_out.cross_chain;
+    let num_operations = options.fan_out.num_operations;
+    let initial_balances = query_balances(&accounts.all_accounts(), &balance).await?;
+    let before = cost_tracker
+        .snapshot(node_service, &accounts.chain_ids())
+        .await?;
+    let time_start = Instant::now();
+    let latencies = submit_groups(runs, submit).await?;
+    if cross_chain {
+        accounts.process_receiver_inbox(node_service).await?;
+    }
+    let elapsed = time_start.elapsed();
+    let average_time = (elapsed.as_millis() as f64) / (num_operations as f64);
+    println!("Average runtime for {variant} transfer={average_time}");
+    options.submission.report(&variant, &latencies);
+    if !cross_chain {
+        accounts.process_receiver_inbox(node_service).await?;
+    }
+    let after = cost_tracker
+        .snapshot(node_service, &accounts.chain_ids())
+        .await?;
+    let costs = cost_tracker.costs(&before, &after);
+    println!("Costs for {variant} transfer: {costs}");
+    let expected = accounts.expected_balances(&initial_balances, amount);
+    verify_balances(&variant, &expected, &balance).await?;
+    Ok(Measurement {
+        variant,
+        num_operations,
+        elapsed,
+        costs,
+    })
+}
@@ -0,0 +1,392 @@
+//! Benchmarks of the fungible-no-graphql operations other than `Transfer`:
+//! * `Approve` followed by `TransferFrom`, which go through the allowances map,
+//! * `Claim`, which pulls the tokens from another chain with a `Withdraw` message,
+//!   answered by a `Credit` message.
+//!
+//! The clients of the local net accept every message, so no `Credit` message bounces
+//! here: the bouncing of claimed tokens is tested in `fungible-no-graphql/tests`.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, ChainId},
+    time::Instant,
+    vm::VmRuntime,
+};
+use linera_service::cli_wrappers::{
+    local_net::{get_node_port, LocalNet, ProcessInbox},
+    ClientWrapper, LineraNet,
+};
+
+use crate::{
+    build_application,
+    costs::CostTracker,
+    invariants::{
+        query_balances, query_no_graphql_balance, query_no_graphql_total_balance, verify_balances,
+        verify_total_supply,
+    },
+    latency::submit_groups,
+    report::Measurement,
+    Options,
+};
+
+/// The sizes of the groups in which `count` operations are submitted.
+fn group_sizes(options: &Options, count: usize) -> Vec<usize> {
+    let group_size = options.submission.group_size(count);
+    (0..count)
+        .step_by(group_size)
+        .map(|start| group_size.min(count - start))
+        .collect()
+}
+
+/// Each claimer has its own chain and owns tokens on the default chain. The claims are
+/// executed on the chain of the claimer and the measured time runs until the inboxes of
+/// the default chain (`Withdraw`) and of the claimers (`Credit`) are processed.
+pub async fn end_to_end_repeated_claim(
+    client: &ClientWrapper,
+    cost_tracker: &CostTracker,
+    options: &Options,
+) -> Result<Measurement> {
+    use fungible::{InitialState, Parameters};
+    use fungible_no_graphql::{
+        FungibleNoGraphQlRequest, FungibleNoGraphQlTokenAbi, FungibleOperation,
+    };
+    let fan_out = &options.fan_out;
+    tracing::info!("Starting repeated claim in fungible-no-graphql");
+
+    let chain_id = client.load_wallet()?.default_chain().unwrap();
+    let mut claimers = Vec::new();
+    for _ in 0..fan_out.num_senders {
+        let (claimer_chain_id, owner) = client
+            .open_chain(chain_id, None, Amount::from_tokens(10))
+            .await?;
+        claimers.push(Account {
+            chain_id: claimer_chain_id,
+            owner,
+        });
+    }
+    // Claim `k` is done by the claimer `k % num_senders`.
+    let num_claims = (0..claimers.len())
+        .map(|index| {
+            (0..fan_out.num_operations)
+                .filter(|k| k % claimers.len() == index)
+                .count()
+        })
+        .collect::<Vec<_>>();
+
+    let (contract_path, service_path) = build_application(client, "fungible-no-graphql").await?;
+    let params = Parameters::new("NAT");
+    let accounts = claimers
+        .iter()
+        .zip(&num_claims)
+        .filter(|(_, count)| **count > 0)
+        .map(|(claimer, count)| (claimer.owner, Amount::from_tokens(*count as u128)))
+        .collect::<BTreeMap<_, _>>();
+    let minted = accounts.values().fold(Amount::ZERO, |total, balance| {
+        total.saturating_add(*balance)
+    });
+    let state = InitialState { accounts };
+    let application_id = client
+        .publish_and_create::<FungibleNoGraphQlTokenAbi, Parameters, InitialState>(
+            contract_path,
+            service_path,
+            VmRuntime::Wasm,
+            &params,
+            &state,
+            &[],
+            None,
+        )
+        .await?;
+
+    let port = get_node_port().await;
+    let mut node_service = client.run_node_service(port, ProcessInbox::Skip).await?;
+
+    let mut runs = Vec::new();
+    for (claimer, count) in claimers.iter().zip(&num_claims) {
+        let source_account = Account {
+            chain_id,
+            owner: claimer.owner,
+        };
+        let queries = group_sizes(options, *count)
+            .into_iter()
+            .map(|size| {
+                let operations = (0..size)
+                    .map(|_| FungibleOperation::Claim {
+                        source_account,
+                        amount: Amount::ONE,
+                        target_account: *claimer,
+                    })
+                    .collect();
+                FungibleNoGraphQlRequest::Operations { operations }
+            })
+            .collect::<Vec<_>>();
+        if !queries.is_empty() {
+            runs.push((
+                node_service.make_application(&claimer.chain_id, &application_id)?,
+                queries,
+            ));
+        }
+    }
+
+    let mut chain_ids = claimers
+        .iter()
+        .map(|claimer| claimer.chain_id)
+        .collect::<Vec<_>>();
+    chain_ids.push(chain_id);
+    let node_service_ref = &node_service;
+    let balance = |account| query_no_graphql_balance(node_service_ref, application_id, account);
+    // The tokens of each claimer move from the default chain to its own chain.
+    let claim_accounts = claimers
+        .iter()
+        .flat_map(|claimer| {
+            let source_account = Account {
+                chain_id,
+                owner: claimer.owner,
+            };
+            [source_account, *claimer]
+        })
+        .collect::<Vec<_>>();
+    let initial_balances = query_balances(&claim_accounts, &balance).await?;
+    let before = cost_tracker.snapshot(&node_service, &chain_ids).await?;
+    let time_start = Instant::now();
+    let latencies = submit_groups(&runs, |app, query| async move {
+        app.run_json_query(query).await?;
+        Ok(())
+    })
+    .await?;
+    node_service.process_inbox(&chain_id).await?;
+    for claimer in &claimers {
+        node_service.process_inbox(&claimer.chain_id).await?;
+    }
+    let elapsed = time_start.elapsed();
+    let average_time = (elapsed.as_millis() as f64) / (fan_out.num_operations as f64);
+    println!("Average runtime for fungible-no-graphql claim={average_time}");
+    let variant = "fungible-no-graphql claim".to_string();
+    options.submission.report(&variant, &latencies);
+    let after = cost_tracker.snapshot(&node_service, &chain_ids).await?;
+    let costs = cost_tracker.costs(&before, &after);
+    println!("Costs for {variant}: {costs}");
+    let expected = initial_balances
+        .iter()
+        .map(|(account, initial)| {
+            let index = claimers
+                .iter()
+                .position(|claimer| claimer.owner == account.owner)
+                .unwrap();
+            let claimed = Amount::from_tokens(num_claims[index] as u128);
+            if account.chain_id == chain_id {
+                (*account, initial.saturating_sub(claimed))
+            } else {
+                (*account, initial.saturating_add(claimed))
+            }
+        })
+        .collect::<Vec<_>>();
+    verify_balances(&variant, &expected, &balance).await?;
+    let total_balance =
+        |chain_id| query_no_graphql_total_balance(node_service_ref, application_id, chain_id);
+    verify_total_supply(&variant, minted, &chain_ids, total_balance).await?;
+
+    node_service.ensure_is_running()?;
+    Ok(Measurement {
+        variant,
+        num_operations: fan_out.num_operations,
+        elapsed,
+        costs,
+    })
+}
+
+/// The owner and the spender are two clients sharing a multi-owner chain, since the
+/// approvals are signed by the owner and the transfers by the spender. The approvals
+/// and the transfers are measured separately.
+pub async fn end_to_end_repeated_transfer_from(
+    net: &mut LocalNet,
+    client: &ClientWrapper,
+    cost_tracker: &CostTracker,
+    options: &Options,
+) -> Result<Vec<Measurement>> {
+    use fungible::{InitialState, Parameters};
+    use fungible_no_graphql::{
+        FungibleNoGraphQlRequest, FungibleNoGraphQlTokenAbi, FungibleOperation,
+    };
+    let num_operations = options.fan_out.num_operations;
+    tracing::info!("Starting repeated approve and transfer-from in fungible-no-graphql");
+
+    let chain_id = client.load_wallet()?.default_chain().unwrap();
+    let account_owner1 = client.get_owner().unwrap();
+
+    let spender_client = net.make_client().await;
+    spender_client.wallet_init(None).await?;
+    let owner = client.keygen().await?;
+    let spender = spender_client.keygen().await?;
+    let shared_chain_id: ChainId = client
+        .open_multi_owner_chain(
+            chain_id,
+            vec![owner, spender],
+            vec![100, 100],
+            u32::MAX,
+            Amount::from_tokens(10),
+            10_000,
+        )
+        .await?;
+    client.assign(owner, shared_chain_id).await?;
+    spender_client.assign(spender, shared_chain_id).await?;
+    let target_account = Account {
+        chain_id: shared_chain_id,
+        owner: client.keygen().await?,
+    };
+
+    let (contract_path, service_path) = build_application(client, "fungible-no-graphql").await?;
+    let params = Parameters::new("NAT");
+    let minted = Amount::from_tokens(num_operations as u128);
+    let accounts = BTreeMap::from([(account_owner1, minted)]);
+    let state = InitialState { accounts };
+    let application_id = client
+        .publish_and_create::<FungibleNoGraphQlTokenAbi, Parameters, InitialState>(
+            contract_path,
+            service_path,
+            VmRuntime::Wasm,
+            &params,
+            &state,
+            &[],
+            None,
+        )
+        .await?;
+
+    let port = get_node_port().await;
+    let mut node_service = client.run_node_service(port, ProcessInbox::Skip).await?;
+    let port = get_node_port().await;
+    let mut spender_node_service = spender_client
+        .run_node_service(port, ProcessInbox::Skip)
+        .await?;
+
+    let funding = FungibleOperation::Transfer {
+        owner: account_owner1,
+        amount: minted,
+        target_account: Account {
+            chain_id: shared_chain_id,
+            owner,
+        },
+    };
+    node_service
+        .make_application(&chain_id, &application_id)?
+        .run_json_query(&FungibleNoGraphQlRequest::Operation { operation: funding })
+        .await?;
+    node_service.process_inbox(&shared_chain_id).await?;
+
+    let owner_app = node_service.make_application(&shared_chain_id, &application_id)?;
+    let spender_app = spender_node_service.make_application(&shared_chain_id, &application_id)?;
+    let approvals = group_sizes(options, num_operations)
+        .into_iter()
+        .map(|size| {
+            let operations = (0..size)
+                .map(|_| FungibleOperation::Approve {
+                    owner,
+                    spender,
+                    allowance: Amount::ONE,
+                })
+                .collect();
+            FungibleNoGraphQlRequest::Operations { operations }
+        })
+        .collect::<Vec<_>>();
+    let transfers = group_sizes(options, num_operations)
+        .into_iter()
+        .map(|size| {
+            let operations = (0..size)
+                .map(|_| FungibleOperation::TransferFrom {
+                    owner,
+                    spender,
+                    amount: Amount::ONE,
+                    target_account,
+                })
+                .collect();
+            FungibleNoGraphQlRequest::Operations { operations }
+        })
+        .collect::<Vec<_>>();
+
+    let owner_account = Account {
+        chain_id: shared_chain_id,
+        owner,
+    };
+    let node_service_ref = &node_service;
+    let balance = |account| query_no_graphql_balance(node_service_ref, application_id, account);
+    let initial_balances = query_balances(&[owner_account, target_account], &balance).await?;
+
+    let mut measurements = Vec::new();
+    for (variant, node_service, runs) in [
+        (
+            "fungible-no-graphql approve",
+            &node_service,
+            [(owner_app, approvals)],
+        ),
+        (
+            "fungible-no-graphql transfer-from",
+            &spender_node_service,
+            [(spender_app, transfers)],
+        ),
+    ] {
+        let before = cost_tracker
+            .snapshot(node_service, &[shared_chain_id])
+            .await?;
+        let time_start = Instant::now();
+        let latencies = submit_groups(&runs, |app, query| async move {
+            app.run_json_query(query).await?;
+            Ok(())
+        })
+        .await?;
+        let elapsed = time_start.elapsed();
+        let average_time = (elapsed.as_millis() as f64) / (num_operations as f64);
+        println!("Average runtime for {variant}={average_time}");
+        options.submission.report(variant, &latencies);
+        let after = cost_tracker
+            .snapshot(node_service, &[shared_chain_id])
+            .await?;
+        let costs = cost_tracker.costs(&before, &after);
+        println!("Costs for {variant}: {costs}");
+        measurements.push(Measurement {
+            variant: variant.to_string(),
+            num_operations,
+            elapsed,
+            costs,
+        });
+    }
+
+    // The transfers were signed by the spender, whose node service is the one that is sure
+    // to be up to date with the shared chain.
+    let spender_node_service_ref = &spender_node_service;
+    let balance =
+        |account| query_no_graphql_balance(spender_node_service_ref, application_id, account);
+    let transferred = Amount::from_tokens(num_operations as u128);
+    let expected = [
+        (
+            owner_account,
+            initial_balances[0].1.saturating_sub(transferred),
+        ),
+        (
+            target_account,
+            initial_balances[1].1.saturating_add(transferred),
+        ),
+    ];
+    verify_balances("fungible-no-graphql transfer-from", &expected, &balance).await?;
+    // The tokens were minted on the default chain and funded the owner on the shared chain.
+    let total_balance = |chain_id| {
+        let node_service = if chain_id == shared_chain_id {
+            spender_node_service_ref
+        } else {
+            node_service_ref
+        };
+        query_no_graphql_total_balance(node_service, application_id, chain_id)
+    };
+    verify_total_supply(
+        "fungible-no-graphql transfer-from",
+        minted,
+        &[chain_id, shared_chain_id],
+        total_balance,
+    )
+    .await?;
+
+    node_service.ensure_is_running()?;
+    spender_node_service.ensure_is_running()?;
+    Ok(measurements)
+}
@@ -0,0 +1,48 @@
+//! The summaries of measured latencies shared by the benchmarks.
+
+/// Nearest-rank percentile of sorted samples.
+pub fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Counts the latencies (in micros) falling in power-of-two buckets.
+pub fn latency_histogram(latencies: &[f64]) -> Vec<(u64, usize)> {
+    let mut histogram: Vec<(u64, usize)> = Vec::new();
+    for &latency in latencies {
+        let bound = (latency.max(1.0).ceil() as u64).next_power_of_two();
+        match histogram.iter_mut().find(|(upper, _)| *upper == bound) {
+            Some((_, count)) => *count += 1,
+            None => histogram.push((bound, 1)),
+        }
+    }
+    histogram.sort();
+    histogram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let sorted = (1..=100).map(f64::from).collect::<Vec<_>>();
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 50.0), 50.0);
+        assert_eq!(percentile(&sorted, 95.0), 95.0);
+        assert_eq!(percentile(&sorted, 99.0), 99.0);
+        assert_eq!(percentile(&sorted, 100.0), 100.0);
+        assert_eq!(percentile(&[1.0, 2.0, 3.0], 50.0), 2.0);
+        assert_eq!(percentile(&[7.0], 99.0), 7.0);
+    }
+
+    #[test]
+    fn test_latency_histogram() {
+        let latencies = [0.5, 1.0, 3.0, 4.0, 5.0, 1000.0];
+        assert_eq!(
+            latency_histogram(&latencies),
+            vec![(1, 2), (4, 2), (8, 1), (1024, 1)]
+        );
+        assert!(latency_histogram(&[]).is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+//! Deployment of several contracts of one compilation. The libraries are deployed first,
+//! and their addresses are written in the bytecode of the contracts linking them.
+
+use std::collections::{BTreeMap, HashMap};
+
+use alloy_primitives::Address;
+use anyhow::{bail, ensure, Result};
+use linera_base::vm::EvmInstantiation;
+use linera_sdk::{
+    abis::evm::EvmAbi,
+    linera_base_types::{ApplicationId, ChainId},
+};
+use linera_service::cli_wrappers::ClientWrapper;
+
+use crate::{
+    solc::{Compilation, CompiledContract},
+    solidity::publish_evm_module,
+};
+
+pub struct Deployer<'a> {
+    client: &'a ClientWrapper,
+    compilation: &'a Compilation,
+    publisher: Option<ChainId>,
+    /// The constructor argument and the instantiation argument of the contracts, by
+    /// name. The other contracts get empty ones.
+    pub arguments: HashMap<String, (Vec<u8>, EvmInstantiation)>,
+}
+
+impl<'a> Deployer<'a> {
+    pub fn new(
+        client: &'a ClientWrapper,
+        compilation: &'a Compilation,
+        publisher: Option<ChainId>,
+    ) -> Self {
+        Deployer {
+            client,
+            compilation,
+            publisher,
+            arguments: HashMap::new(),
+        }
+    }
+
+    /// Deploys the contracts called `names` and the libraries they link.
+    pub async fn deploy(&self, names: &[&str]) -> Result<BTreeMap<String, ApplicationId<EvmAbi>>> {
+        let contracts = names
+            .iter()
+            .map(|name| self.compilation.contract_named(name))
+            .collect::<Result<Vec<_>>>()?;
+        for contract in &contracts {
+            ensure!(
+                contract.is_deployable(),
+                "{} has no bytecode to deploy",
+                contract.name
+            );
+        }
+        self.deploy_contracts(contracts).await
+    }
+
+    /// Deploys all the contracts that have a bytecode. Only the tests deploy a whole
+    /// compilation: the scenarios deploy the test contract, which deploys the others.
+    #[cfg(test)]
+    pub async fn deploy_all(&self) -> Result<BTreeMap<String, ApplicationId<EvmAbi>>> {
+        let contracts = self
+            .compilation
+            .contracts()
+            .filter(|contract| contract.is_deployable())
+            .collect();
+        self.deploy_contracts(contracts).await
+    }
+
+    async fn deploy_contracts(
+        &self,
+        contracts: Vec<&'a CompiledContract>,
+    ) -> Result<BTreeMap<String, ApplicationId<EvmAbi>>> {
+        let mut order = Vec::new();
+        for contract in contracts {
+            self.visit(contract, &mut Vec::new(), &mut order)?;
+        }
+        for (index, contract) in order.iter().enumerate() {
+            if order[..index]
+                .iter()
+                .any(|other| other.name == contract.name)
+            {
+                bail!("several contracts to deploy are called {}", contract.name);
+            }
+        }
+        let mut addresses = BTreeMap::<(String, String), Address>::new();
+        let mut application_ids = BTreeMap::new();
+        for contract in order {
+            let (constructor_argument, evm_instantiation) = self
+                .arguments
+                .get(&contract.name)
+                .cloned()
+                .unwrap_or_default();
+            let module = contract.link(&addresses)?;
+            let application_id = publish_evm_module(
+                self.client,
+                module,
+                &constructor_argument,
+                &evm_instantiation,
+                self.publisher,
+            )
+            .await?;
+            tracing::info!(
+                "Deployed {}:{} as {application_id:?}",
+                contract.file_name,
+                contract.name
+            );
+            addresses.insert(
+                (contract.file_name.clone(), contract.name.clone()),
+                application_id.evm_address(),
+            );
+            application_ids.insert(contract.name.clone(), application_id);
+        }
+        Ok(application_ids)
+    }
+
+    /// Appends `contract` to `order` after the libraries it links, unless it is already there.
+    fn visit(
+        &self,
+        contract: &'a CompiledContract,
+        path: &mut Vec<&'a CompiledContract>,
+        order: &mut Vec<&'a CompiledContract>,
+    ) -> Result<()> {
+        if order.iter().any(|other| std::ptr::eq(*other, contract)) {
+            return Ok(());
+        }
+        if path.iter().any(|other| std::ptr::eq(*other, contract)) {
+            bail!("the libraries linked by {} depend on it", contract.name);
+        }
+        path.push(contract);
+        for reference in &contract.link_references {
+            let (file_name, name) = &reference.library;
+            let library = self.compilation.contract(file_name, name)?;
+            self.visit(library, path, order)?;
+        }
+        path.pop();
+        order.push(contract);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::fixture::{compile_test_contract, get_config, Network};
+
+    /// Deploys every contract of the test compilation, the libraries first, on a local net.
+    #[tokio::test]
+    #[ignore = "starts a local net"]
+    async fn test_deploy_all() -> Result<()> {
+        let compilation = compile_test_contract()?;
+        let network = Network::new(get_config()).await?;
+        let deployer = Deployer::new(
+            network.regular().client(),
+            &compilation,
+            Some(network.chain_id()),
+        );
+        let application_ids = deployer.deploy_all().await;
+        network.terminate().await?;
+        let application_ids = application_ids?;
+        let deployable = compilation
+            .contracts()
+            .filter(|contract| contract.is_deployable())
+            .map(|contract| contract.name.clone())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(
+            application_ids.keys().cloned().collect::<BTreeSet<_>>(),
+            deployable
+        );
+        let addresses = application_ids
+            .values()
+            .map(|application_id| application_id.evm_address())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(addresses.len(), application_ids.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,381 @@
+//! The setup shared by the Morpho scenarios. A `Network` holds the local net and the
+//! six users of a multi-owner chain. A `Fixture` deploys a fresh test contract on it,
+//! which deploys Morpho, the tokens, the oracle and the IRM, and creates the market.
+//! Since every scenario gets its own contracts, one network can host many scenarios.
+
+use std::str::FromStr;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolCall;
+use anyhow::Result;
+use linera_base::vm::{EvmInstantiation, EvmOperation, EvmQuery};
+use linera_sdk::{
+    abis::evm::EvmAbi,
+    linera_base_types::{Account, AccountOwner, Amount, ApplicationId, ChainId},
+};
+use linera_service::cli_wrappers::{
+    local_net::{get_node_port, Database, LocalNet, LocalNetConfig, ProcessInbox},
+    ClientWrapper, LineraNet, LineraNetConfig, Network as NetworkKind, NodeService,
+};
+
+use crate::{
+    deploy::Deployer,
+    morpho::*,
+    solc::{Compilation, Solc, SourceTree},
+    solidity::query_evm,
+};
+
+fn get_zero_operation(calldata: Vec<u8>) -> Result<EvmQuery, bcs::Error> {
+    let operation = EvmOperation::new(Amount::ZERO, calldata);
+    operation.to_evm_query()
+}
+
+/// Compiles `SimpleNonReentrantTest.sol` and the Morpho sources it imports.
+pub fn compile_test_contract() -> Result<Compilation> {
+    let tree = SourceTree::new("morpho_test_code", &["test/SimpleNonReentrantTest.sol"]);
+    Solc::locate()?.compile(&tree)
+}
+
+pub fn get_config() -> LocalNetConfig {
+    let mut config = LocalNetConfig::new_test(Database::Service, NetworkKind::Grpc);
+    config.num_initial_validators = 1;
+    config.num_shards = 1;
+    config
+}
+
+fn evm_application_id(address: Address) -> ApplicationId<EvmAbi> {
+    ApplicationId::from(address).with_abi::<EvmAbi>()
+}
+
+/// A user of the shared chain, with its own wallet.
+pub struct User {
+    client: ClientWrapper,
+    owner: AccountOwner,
+}
+
+impl User {
+    async fn new(net: &mut LocalNet) -> Result<User> {
+        let client = net.make_client().await;
+        client.wallet_init(None).await?;
+        let owner = client.keygen().await?;
+        Ok(User { client, owner })
+    }
+
+    pub fn client(&self) -> &ClientWrapper {
+        &self.client
+    }
+
+    pub async fn start(&self, chain_id: ChainId) -> Result<UserService> {
+        let port = get_node_port().await;
+        let node_service = self
+            .client
+            .run_node_service(port, ProcessInbox::Skip)
+            .await?;
+        Ok(UserService {
+            node_service,
+            chain_id,
+            address: self.owner.to_evm_address().unwrap(),
+        })
+    }
+}
+
+/// The node service of a user during a scenario.
+pub struct UserService {
+    node_service: NodeService,
+    chain_id: ChainId,
+    pub address: Address,
+}
+
+impl UserService {
+    pub async fn process_inbox(&self) -> Result<()> {
+        self.node_service.process_inbox(&self.chain_id).await?;
+        Ok(())
+    }
+
+    /// Processes the inbox of the shared chain, then executes `call` on the contract,
+    /// in a block signed by this user.
+    pub async fn execute(
+        &self,
+        application_id: ApplicationId<EvmAbi>,
+        call: impl SolCall,
+    ) -> Result<()> {
+        self.execute_calldata(application_id, call.abi_encode())
+            .await
+    }
+
+    /// Same as `execute`, for a call that is already encoded.
+    pub async fn execute_calldata(
+        &self,
+        application_id: ApplicationId<EvmAbi>,
+        calldata: Vec<u8>,
+    ) -> Result<()> {
+        self.process_inbox().await?;
+        self.submit_calldata(application_id, calldata).await
+    }
+
+    /// Executes `call` without processing the inbox first, so that an error comes from
+    /// the operation itself. Used for the calls that are expected to revert.
+    pub async fn submit(
+        &self,
+        application_id: ApplicationId<EvmAbi>,
+        call: impl SolCall,
+    ) -> Result<()> {
+        self.submit_calldata(application_id, call.abi_encode())
+            .await
+    }
+
+    /// Same as `submit`, for a call that is already encoded.
+    pub async fn submit_calldata(
+        &self,
+        application_id: ApplicationId<EvmAbi>,
+        calldata: Vec<u8>,
+    ) -> Result<()> {
+        let application = self
+            .node_service
+            .make_application(&self.chain_id, &application_id)?;
+        application
+            .run_json_query(get_zero_operation(calldata)?)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn query<C: SolCall>(
+        &self,
+        application_id: ApplicationId<EvmAbi>,
+        call: C,
+    ) -> Result<C::Return> {
+        let application = self
+            .node_service
+            .make_application(&self.chain_id, &application_id)?;
+        query_evm(&application, call).await
+    }
+
+    pub fn ensure_is_running(&mut self) -> Result<()> {
+        self.node_service.ensure_is_running()
+    }
+}
+
+/// The local net with a chain owned by the six users of the scenarios.
+pub struct Network {
+    net: LocalNet,
+    chain_id: ChainId,
+    regular: User,
+    owner: User,
+    supplier: User,
+    borrower: User,
+    liquidator: User,
+    supplier2: User,
+}
+
+impl Network {
+    pub async fn new(config: LocalNetConfig) -> Result<Network> {
+        tracing::info!("Creating the clients and the multi-owner chain");
+        let (mut net, client_regular) = config.instantiate().await?;
+        let chain1 = *client_regular
+            .load_wallet()?
+            .owned_chain_ids()
+            .first()
+            .unwrap();
+        let regular = User {
+            owner: client_regular.keygen().await?,
+            client: client_regular,
+        };
+        let owner = User::new(&mut net).await?;
+        let supplier = User::new(&mut net).await?;
+        let borrower = User::new(&mut net).await?;
+        let liquidator = User::new(&mut net).await?;
+        let supplier2 = User::new(&mut net).await?;
+        let users = [
+            &regular,
+            &owner,
+            &supplier,
+            &borrower,
+            &liquidator,
+            &supplier2,
+        ];
+
+        // Open a chain owned by all six users
+        let chain_id = regular
+            .client
+            .open_multi_owner_chain(
+                chain1,
+                users.iter().map(|user| user.owner).collect(),
+                vec![100; users.len()],
+                u32::MAX,
+                Amount::from_tokens(1000),
+                10_000,
+            )
+            .await?;
+        for user in users {
+            user.client.assign(user.owner, chain_id).await?;
+        }
+
+        let account1 = Account {
+            chain_id,
+            owner: regular.owner,
+        };
+        let account_chain = Account::chain(chain_id);
+        regular
+            .client
+            .transfer_with_accounts(Amount::from_tokens(800), account_chain, account1)
+            .await?;
+        assert_eq!(
+            regular.client.local_balance(account_chain).await?,
+            Amount::from_micros(199999990)
+        );
+        assert_eq!(
+            regular.client.local_balance(account1).await?,
+            Amount::from_tokens(800)
+        );
+        assert_eq!(
+            regular.client.query_balance(account_chain).await?,
+            Amount::from_micros(199999990)
+        );
+        assert_eq!(
+            regular.client.query_balance(account1).await?,
+            Amount::from_tokens(800)
+        );
+
+        Ok(Network {
+            net,
+            chain_id,
+            regular,
+            owner,
+            supplier,
+            borrower,
+            liquidator,
+            supplier2,
+        })
+    }
+
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
+    /// The user owning the default chain of the local net.
+    pub fn regular(&self) -> &User {
+        &self.regular
+    }
+
+    pub async fn terminate(mut self) -> Result<()> {
+        self.net.ensure_is_running().await?;
+        self.net.terminate().await?;
+        Ok(())
+    }
+}
+
+/// The contracts of one scenario and the node services of the users.
+pub struct Fixture {
+    pub regular: UserService,
+    pub owner: UserService,
+    pub supplier: UserService,
+    pub borrower: UserService,
+    pub liquidator: UserService,
+    pub supplier2: UserService,
+    pub test_contract: ApplicationId<EvmAbi>,
+    pub test_contract_address: Address,
+    pub morpho: ApplicationId<EvmAbi>,
+    pub loan_token: ApplicationId<EvmAbi>,
+    pub collateral_token: ApplicationId<EvmAbi>,
+    pub oracle: ApplicationId<EvmAbi>,
+    pub market_params: MarketParams,
+}
+
+impl Fixture {
+    /// Deploys the test contract of `compilation`, runs its setup and creates the market,
+    /// with the same approvals as the `setUp` function of the Solidity test.
+    pub async fn deploy(network: &Network, compilation: &Compilation) -> Result<Fixture> {
+        let chain_id = network.chain_id;
+        let evm_instantiation = EvmInstantiation {
+            value: Amount::from_tokens(100).into(),
+            argument: vec![],
+        };
+        let mut deployer = Deployer::new(&network.regular.client, compilation, Some(chain_id));
+        deployer.arguments.insert(
+            "SimpleNonReentrantTest".to_string(),
+            (Vec::new(), evm_instantiation),
+        );
+        let test_contract =
+            deployer.deploy(&["SimpleNonReentrantTest"]).await?["SimpleNonReentrantTest"];
+        tracing::info!("Deployed the test contract {test_contract:?}");
+
+        let regular = network.regular.start(chain_id).await?;
+        let owner = network.owner.start(chain_id).await?;
+        let supplier = network.supplier.start(chain_id).await?;
+        let borrower = network.borrower.start(chain_id).await?;
+        let liquidator = network.liquidator.start(chain_id).await?;
+        let supplier2 = network.supplier2.start(chain_id).await?;
+
+        let operation = set_addressesCall {
+            ownerAddress: owner.address,
+            supplierAddress: supplier.address,
+            borrowerAddress: borrower.address,
+            liquidatorAddress: liquidator.address,
+            supplier2Address: supplier2.address,
+        };
+        regular.execute(test_contract, operation).await?;
+        regular.execute(test_contract, set_up_part_aCall {}).await?;
+        let irm = regular.query(test_contract, get_irmCall {}).await?;
+        let oracle = regular.query(test_contract, get_oracleCall {}).await?;
+        let morpho = regular.query(test_contract, get_morphoCall {}).await?;
+        let morpho_id = evm_application_id(morpho);
+
+        let lltv = U256::from_str("800000000000000000")?;
+        owner.execute(morpho_id, enableIrmCall { irm }).await?;
+        owner.execute(morpho_id, enableLltvCall { lltv }).await?;
+        regular.execute(test_contract, set_up_part_cCall {}).await?;
+
+        let loan_token = regular.query(test_contract, get_loan_tokenCall {}).await?;
+        let collateral_token = regular
+            .query(test_contract, get_collateral_tokenCall {})
+            .await?;
+        let loan_token_id = evm_application_id(loan_token);
+        let collateral_token_id = evm_application_id(collateral_token);
+        let approval = approveCall {
+            spender: morpho,
+            amount: U256::MAX,
+        };
+        for user in [&supplier, &borrower, &liquidator, &supplier2] {
+            user.execute(loan_token_id, approval.clone()).await?;
+        }
+        borrower.execute(collateral_token_id, approval).await?;
+
+        let market_params = MarketParams {
+            loanToken: loan_token,
+            collateralToken: collateral_token,
+            oracle,
+            irm,
+            lltv,
+        };
+        tracing::info!("Created the market of the scenario");
+        Ok(Fixture {
+            regular,
+            owner,
+            supplier,
+            borrower,
+            liquidator,
+            supplier2,
+            test_contract,
+            test_contract_address: test_contract.evm_address(),
+            morpho: morpho_id,
+            loan_token: loan_token_id,
+            collateral_token: collateral_token_id,
+            oracle: evm_application_id(oracle),
+            market_params,
+        })
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        for user in [
+            &mut self.regular,
+            &mut self.owner,
+            &mut self.supplier,
+            &mut self.borrower,
+            &mut self.liquidator,
+            &mut self.supplier2,
+        ] {
+            user.ensure_is_running()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//! Runs the tests of a Foundry test contract on Linera's EVM, as `forge test` does: for
+//! every test function, a fresh instance of the contract is deployed, `setUp` is executed
+//! if the contract has one, and then the test function, each in its own operation. A test
+//! passes if its operation succeeds, or, for a `testFail*` function, if it reverts.
+//!
+//! Without `vm.prank`, every call of the tests comes from the test contract itself, so
+//! a contract with the `set_addresses` function of the Morpho tests is given its own
+//! address for all the roles before `setUp`. In particular it owns the Morpho instance
+//! it creates there.
+
+use alloy_primitives::keccak256;
+use anyhow::{ensure, Context, Result};
+
+use crate::{
+    deploy::Deployer,
+    fixture::Network,
+    morpho::set_addressesCall,
+    solc::{Compilation, CompiledContract},
+};
+
+/// The calldata of a function without parameters.
+fn selector(name: &str) -> Vec<u8> {
+    keccak256(format!("{name}()"))[..4].to_vec()
+}
+
+/// The test functions of a contract, found in its ABI.
+pub struct TestSuite {
+    contract_name: String,
+    has_set_up: bool,
+    has_set_addresses: bool,
+    pub tests: Vec<String>,
+    /// The test functions with parameters, i.e. the fuzz tests, which are not run.
+    pub fuzz_tests: Vec<String>,
+}
+
+impl TestSuite {
+    pub fn from_abi(contract: &CompiledContract) -> Result<TestSuite> {
+        let entries = contract
+            .abi
+            .as_array()
+            .with_context(|| format!("the ABI of {} is not an array", contract.name))?;
+        let mut suite = TestSuite {
+            contract_name: contract.name.clone(),
+            has_set_up: false,
+            has_set_addresses: false,
+            tests: Vec::new(),
+            fuzz_tests: Vec::new(),
+        };
+        for entry in entries.iter().filter(|entry| entry["type"] == "function") {
+            let Some(name) = entry["name"].as_str() else {
+                continue;
+            };
+            let has_parameters = entry["inputs"]
+                .as_array()
+                .is_some_and(|inputs| !inputs.is_empty());
+            if name == "setUp" {
+                ensure!(!has_parameters, "setUp of {} has parameters", contract.name);
+                suite.has_set_up = true;
+            } else if name == "set_addresses" {
+                suite.has_set_addresses = true;
+            } else if name.starts_with("test") {
+                if has_parameters {
+                    suite.fuzz_tests.push(name.to_string());
+                } else {
+                    suite.tests.push(name.to_string());
+                }
+            }
+        }
+        Ok(suite)
+    }
+
+    /// Runs the tests called `names`, or all of them if `names` is empty.
+    pub async fn run(
+        &self,
+        network: &Network,
+        compilation: &Compilation,
+        names: &[&str],
+    ) -> Result<Vec<(String, Result<()>)>> {
+        for name in names {
+            ensure!(
+                self.tests.iter().any(|test| test == name),
+                "{name} is not a test of {} without parameters",
+                self.contract_name
+            );
+        }
+        for name in &self.fuzz_tests {
+            println!("Skipping the fuzz test {name}");
+        }
+        let mut results = Vec::new();
+        for name in &self.tests {
+            if names.is_empty() || names.contains(&name.as_str()) {
+                println!("Running {}::{name}", self.contract_name);
+                results.push((
+                    name.clone(),
+                    self.run_test(network, compilation, name).await,
+                ));
+            }
+        }
+        Ok(results)
+    }
+
+    async fn run_test(
+        &self,
+        network: &Network,
+        compilation: &Compilation,
+        name: &str,
+    ) -> Result<()> {
+        let chain_id = network.chain_id();
+        let deployer = Deployer::new(network.regular().client(), compilation, Some(chain_id));
+        let application_id =
+            deployer.deploy(&[self.contract_name.as_str()]).await?[&self.contract_name];
+        let mut user = network.regular().start(chain_id).await?;
+        if self.has_set_addresses {
+            let address = application_id.evm_address();
+            let operation = set_addressesCall {
+                ownerAddress: address,
+                supplierAddress: address,
+                borrowerAddress: address,
+                liquidatorAddress: address,
+                supplier2Address: address,
+            };
+            user.execute(application_id, operation)
+                .await
+                .context("set_addresses failed")?;
+        }
+        if self.has_set_up {
+            user.execute_calldata(application_id, selector("setUp"))
+                .await
+                .context("setUp failed")?;
+        }
+        // An inbox failure must not pass for the revert expected by a `testFail*` function.
+        user.process_inbox().await?;
+        let result = user.submit_calldata(application_id, selector(name)).await;
+        user.ensure_is_running()?;
+        if name.starts_with("testFail") {
+            ensure!(result.is_err(), "{name} was expected to revert");
+            Ok(())
+        } else {
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn contract(abi: serde_json::Value) -> CompiledContract {
+        CompiledContract {
+            file_name: "Test.sol".to_string(),
+            name: "Test".to_string(),
+            abi,
+            bytecode_object: "6080".to_string(),
+            link_references: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_from_abi() {
+        let abi = json!([
+            { "type": "constructor", "inputs": [] },
+            { "type": "function", "name": "setUp", "inputs": [] },
+            { "type": "function", "name": "test_Supply", "inputs": [] },
+            { "type": "function", "name": "testFail_Borrow", "inputs": [] },
+            { "type": "function", "name": "testFuzz_Supply", "inputs": [{ "name": "amount", "type": "uint256" }] },
+            { "type": "function", "name": "get_morpho", "inputs": [] },
+            { "type": "event", "name": "testEvent", "inputs": [] },
+        ]);
+        let suite = TestSuite::from_abi(&contract(abi)).unwrap();
+        assert!(suite.has_set_up);
+        assert!(!suite.has_set_addresses);
+        assert_eq!(suite.tests, ["test_Supply", "testFail_Borrow"]);
+        assert_eq!(suite.fuzz_tests, ["testFuzz_Supply"]);
+    }
+
+    #[test]
+    fn test_from_abi_with_set_addresses() {
+        let abi = json!([
+            { "type": "function", "name": "set_addresses", "inputs": [{ "name": "ownerAddress", "type": "address" }] },
+            { "type": "function", "name": "test_Supply", "inputs": [] },
+        ]);
+        let suite = TestSuite::from_abi(&contract(abi)).unwrap();
+        assert!(!suite.has_set_up);
+        assert!(suite.has_set_addresses);
+        assert_eq!(suite.tests, ["test_Supply"]);
+    }
+
+    #[test]
+    fn test_from_abi_errors() {
+        let abi = json!([{ "type": "function", "name": "setUp", "inputs": [{ "name": "x", "type": "uint256" }] }]);
+        assert!(TestSuite::from_abi(&contract(abi)).is_err());
+        assert!(TestSuite::from_abi(&contract(json!({}))).is_err());
+    }
+
+    #[test]
+    fn test_selector() {
+        assert_eq!(selector("setUp"), [0x0a, 0x92, 0x54, 0xe4]);
+    }
+}
@@ -0,0 +1,110 @@
+//! The Solidity interfaces of the Morpho test contract, of Morpho itself and of the
+//! mocked tokens and oracle, as used by the fixture and the scenarios.
+
+use alloy_sol_types::sol;
+
+sol! {
+    struct MarketParams {
+        address loanToken;
+        address collateralToken;
+        address oracle;
+        address irm;
+        uint256 lltv;
+    }
+
+    function test_SimpleSupplyWithdraw();
+    function set_addresses(
+        address ownerAddress,
+        address supplierAddress,
+        address borrowerAddress,
+        address liquidatorAddress,
+        address supplier2Address
+    );
+    function set_up_part_a();
+    function set_up_part_a2();
+    function set_up_part_b();
+    function set_up_part_c();
+    function set_up_part_d();
+    function set_up_part_e();
+    function get_irm() external returns (address);
+    function get_oracle() external returns (address);
+    function get_morpho() external returns (address);
+    function get_loan_token() external returns (address);
+    function get_collateral_token() external returns (address);
+    function id() external view returns (bytes32);
+    function enableIrm(address irm);
+    function enableLltv(uint256 lltv);
+    function approve(address spender, uint256 amount);
+    function setBalance(address owner, uint256 amount);
+    function supply(
+        MarketParams marketParams,
+        uint256 assets,
+        uint256 shares,
+        address onBehalf,
+        bytes data
+    ) external returns (uint256, uint256);
+    function withdraw(
+        MarketParams marketParams,
+        uint256 assets,
+        uint256 shares,
+        address onBehalf,
+        address receiver
+    ) external returns (uint256, uint256);
+    function supplyCollateral(
+        MarketParams marketParams,
+        uint256 assets,
+        address onBehalf,
+        bytes data
+    ) external;
+    function borrow(
+        MarketParams marketParams,
+        uint256 assets,
+        uint256 shares,
+        address onBehalf,
+        address receiver
+    ) external returns (uint256, uint256);
+    function repay(
+        MarketParams marketParams,
+        uint256 assets,
+        uint256 shares,
+        address onBehalf,
+        bytes data
+    ) external returns (uint256, uint256);
+    function withdrawCollateral(
+        MarketParams marketParams,
+        uint256 assets,
+        address onBehalf,
+        address receiver
+    ) external;
+    function market(bytes32 id) external view returns (
+        uint128 totalSupplyAssets,
+        uint128 totalSupplyShares,
+        uint128 totalBorrowAssets,
+        uint128 totalBorrowShares,
+        uint128 lastUpdate,
+        uint128 fee
+    );
+    function balanceOf(address owner) external view returns (uint256);
+    function setPrice(uint256 price) external;
+    function liquidate(
+        MarketParams marketParams,
+        address borrower,
+        uint256 seizedAssets,
+        uint256 repaidShares,
+        bytes data
+    ) external returns (uint256, uint256);
+    function accrueInterest(MarketParams marketParams) external;
+    function resetCallbackFlags();
+    function supplyCallbackTriggered() external view returns (bool);
+    function repayCallbackTriggered() external view returns (bool);
+    function supplyCollateralCallbackTriggered() external view returns (bool);
+    function liquidateCallbackTriggered() external view returns (bool);
+    function setLoanTokenApproval(uint256 amount);
+    function setCollateralTokenApproval(uint256 amount);
+    function callSupplyWithCallback(uint256 amount, bytes data);
+    function callSupplyCollateral(uint256 amount);
+    function callSupplyCollateralWithCallback(uint256 amount, bytes data);
+    function callBorrow(uint256 amount);
+    function callRepayWithCallback(uint256 amount, bytes data);
+    function callLiquidateWithCallback(address borrowerAddr, uint256 seizedAssets, bytes data);
+}
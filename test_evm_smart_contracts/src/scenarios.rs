@@ -0,0 +1,563 @@
+//! The Morpho scenarios, each a port of a `test_*` function of `SimpleNonReentrantTest.sol`
+//! run on a fresh `Fixture`, and the registry through which the binary selects them.
+
+use alloy_primitives::{Bytes, U256};
+use alloy_sol_types::{sol_data, SolType};
+use anyhow::{ensure, Result};
+use futures::future::{FutureExt, LocalBoxFuture};
+
+use crate::{
+    fixture::{Fixture, UserService},
+    morpho::*,
+};
+
+pub struct Scenario {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub run: for<'a> fn(&'a Fixture) -> LocalBoxFuture<'a, Result<()>>,
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "morpho_supply_withdraw",
+        description: "supply/withdraw",
+        run: |fixture| supply_withdraw(fixture).boxed_local(),
+    },
+    Scenario {
+        name: "morpho_borrow_repay",
+        description: "borrow/repay cycle",
+        run: |fixture| borrow_repay(fixture).boxed_local(),
+    },
+    Scenario {
+        name: "morpho_liquidation",
+        description: "liquidation",
+        run: |fixture| liquidation(fixture).boxed_local(),
+    },
+    Scenario {
+        name: "morpho_interest",
+        description: "interest accrual",
+        run: |fixture| interest(fixture).boxed_local(),
+    },
+    Scenario {
+        name: "morpho_multiple_suppliers",
+        description: "multiple suppliers",
+        run: |fixture| multiple_suppliers(fixture).boxed_local(),
+    },
+    Scenario {
+        name: "morpho_max_borrow",
+        description: "max borrow capacity",
+        run: |fixture| max_borrow(fixture).boxed_local(),
+    },
+    Scenario {
+        name: "morpho_supply_callback",
+        description: "supply with callback",
+        run: |fixture| supply_callback(fixture).boxed_local(),
+    },
+    Scenario {
+        name: "morpho_supply_collateral_callback",
+        description: "supply collateral with callback",
+        run: |fixture| supply_collateral_callback(fixture).boxed_local(),
+    },
+    Scenario {
+        name: "morpho_repay_callback",
+        description: "repay with callback",
+        run: |fixture| repay_callback(fixture).boxed_local(),
+    },
+    Scenario {
+        name: "morpho_liquidate_callback",
+        description: "liquidate with callback",
+        run: |fixture| liquidate_callback(fixture).boxed_local(),
+    },
+];
+
+pub fn find_scenario(name: &str) -> Option<&'static Scenario> {
+    SCENARIOS.iter().find(|scenario| scenario.name == name)
+}
+
+fn ether(amount: u64) -> U256 {
+    U256::from(amount) * U256::from(1_000_000_000_000_000_000u64)
+}
+
+/// The callback data of the test contract: `abi.encode(tag)`.
+fn callback_data(tag: &str) -> Bytes {
+    sol_data::String::abi_encode(&tag.to_string()).into()
+}
+
+/// Mints `amount` loan tokens to `user`, who supplies them to the market.
+async fn supply(fixture: &Fixture, user: &UserService, amount: U256) -> Result<()> {
+    let operation = setBalanceCall {
+        owner: user.address,
+        amount,
+    };
+    fixture
+        .regular
+        .execute(fixture.loan_token, operation)
+        .await?;
+    let operation = supplyCall {
+        marketParams: fixture.market_params.clone(),
+        assets: amount,
+        shares: U256::ZERO,
+        onBehalf: user.address,
+        data: vec![].into(),
+    };
+    user.execute(fixture.morpho, operation).await?;
+    println!("Supplied {amount} by {}", user.address);
+    Ok(())
+}
+
+/// Mints `amount` collateral tokens to the borrower, who supplies them to the market.
+async fn supply_collateral(fixture: &Fixture, amount: U256) -> Result<()> {
+    let operation = setBalanceCall {
+        owner: fixture.borrower.address,
+        amount,
+    };
+    fixture
+        .regular
+        .execute(fixture.collateral_token, operation)
+        .await?;
+    let operation = supplyCollateralCall {
+        marketParams: fixture.market_params.clone(),
+        assets: amount,
+        onBehalf: fixture.borrower.address,
+        data: vec![].into(),
+    };
+    fixture.borrower.execute(fixture.morpho, operation).await?;
+    println!("Supplied {amount} of collateral by the borrower");
+    Ok(())
+}
+
+fn borrow_call(fixture: &Fixture, amount: U256) -> borrowCall {
+    borrowCall {
+        marketParams: fixture.market_params.clone(),
+        assets: amount,
+        shares: U256::ZERO,
+        onBehalf: fixture.borrower.address,
+        receiver: fixture.borrower.address,
+    }
+}
+
+async fn borrow(fixture: &Fixture, amount: U256) -> Result<()> {
+    fixture
+        .borrower
+        .execute(fixture.morpho, borrow_call(fixture, amount))
+        .await?;
+    println!("Borrowed {amount} by the borrower");
+    Ok(())
+}
+
+/// The state of the market, as seen by the regular user.
+async fn market(fixture: &Fixture) -> Result<marketReturn> {
+    let market_id = fixture
+        .regular
+        .query(fixture.test_contract, idCall {})
+        .await?;
+    fixture.regular.process_inbox().await?;
+    fixture
+        .regular
+        .query(fixture.morpho, marketCall { id: market_id })
+        .await
+}
+
+/// The price drops 20%, which makes the position of the borrower unhealthy.
+async fn drop_price(fixture: &Fixture) -> Result<()> {
+    let oracle_price_scale = U256::from(10).pow(U256::from(36));
+    let price = oracle_price_scale * U256::from(80) / U256::from(100);
+    fixture
+        .regular
+        .execute(fixture.oracle, setPriceCall { price })
+        .await?;
+    println!("Oracle price set to 80%");
+    Ok(())
+}
+
+async fn reset_callback_flags(fixture: &Fixture) -> Result<()> {
+    fixture
+        .regular
+        .execute(fixture.test_contract, resetCallbackFlagsCall {})
+        .await
+}
+
+/// test_SimpleSupplyWithdraw
+async fn supply_withdraw(fixture: &Fixture) -> Result<()> {
+    let supply_amount = ether(1000);
+    supply(fixture, &fixture.supplier, supply_amount).await?;
+
+    let market = market(fixture).await?;
+    ensure!(
+        U256::from(market.totalSupplyAssets) == supply_amount,
+        "Total supply mismatch"
+    );
+
+    let withdraw_amount = ether(500);
+    let operation = withdrawCall {
+        marketParams: fixture.market_params.clone(),
+        assets: withdraw_amount,
+        shares: U256::ZERO,
+        onBehalf: fixture.supplier.address,
+        receiver: fixture.supplier.address,
+    };
+    fixture.supplier.execute(fixture.morpho, operation).await?;
+
+    let operation = balanceOfCall {
+        owner: fixture.supplier.address,
+    };
+    let balance = fixture
+        .supplier
+        .query(fixture.loan_token, operation)
+        .await?;
+    ensure!(balance == withdraw_amount, "Withdrawal verification failed");
+    Ok(())
+}
+
+/// test_CompleteBorrowRepayCycle
+async fn borrow_repay(fixture: &Fixture) -> Result<()> {
+    let collateral_amount = ether(1000);
+    let borrow_amount = ether(600);
+    supply(fixture, &fixture.supplier, ether(10000)).await?;
+    supply_collateral(fixture, collateral_amount).await?;
+    borrow(fixture, borrow_amount).await?;
+
+    let operation = balanceOfCall {
+        owner: fixture.borrower.address,
+    };
+    let balance = fixture
+        .borrower
+        .query(fixture.loan_token, operation)
+        .await?;
+    ensure!(balance == borrow_amount, "Borrow failed");
+
+    let operation = repayCall {
+        marketParams: fixture.market_params.clone(),
+        assets: borrow_amount,
+        shares: U256::ZERO,
+        onBehalf: fixture.borrower.address,
+        data: vec![].into(),
+    };
+    fixture.borrower.execute(fixture.morpho, operation).await?;
+    let operation = withdrawCollateralCall {
+        marketParams: fixture.market_params.clone(),
+        assets: collateral_amount,
+        onBehalf: fixture.borrower.address,
+        receiver: fixture.borrower.address,
+    };
+    fixture.borrower.execute(fixture.morpho, operation).await?;
+
+    let operation = balanceOfCall {
+        owner: fixture.borrower.address,
+    };
+    let balance = fixture
+        .borrower
+        .query(fixture.collateral_token, operation)
+        .await?;
+    ensure!(balance == collateral_amount, "Collateral withdrawal failed");
+    let market = market(fixture).await?;
+    ensure!(market.totalBorrowAssets == 0, "Debt not fully repaid");
+    Ok(())
+}
+
+/// test_Liquidation
+async fn liquidation(fixture: &Fixture) -> Result<()> {
+    supply(fixture, &fixture.supplier, ether(10000)).await?;
+    supply_collateral(fixture, ether(1000)).await?;
+    borrow(fixture, ether(700)).await?;
+    drop_price(fixture).await?;
+
+    let operation = setBalanceCall {
+        owner: fixture.liquidator.address,
+        amount: ether(1000),
+    };
+    fixture
+        .regular
+        .execute(fixture.loan_token, operation)
+        .await?;
+    let query = balanceOfCall {
+        owner: fixture.liquidator.address,
+    };
+    let initial_balance = fixture
+        .liquidator
+        .query(fixture.collateral_token, query.clone())
+        .await?;
+
+    let operation = liquidateCall {
+        marketParams: fixture.market_params.clone(),
+        borrower: fixture.borrower.address,
+        seizedAssets: ether(100),
+        repaidShares: U256::ZERO,
+        data: vec![].into(),
+    };
+    fixture
+        .liquidator
+        .execute(fixture.morpho, operation)
+        .await?;
+
+    // The Solidity test compares the balance with the seized amount returned by
+    // `liquidate`, which the operation does not return here: we check that it increased.
+    let final_balance = fixture
+        .liquidator
+        .query(fixture.collateral_token, query)
+        .await?;
+    ensure!(
+        final_balance > initial_balance,
+        "Liquidation failed - no collateral seized"
+    );
+    println!("Collateral seized: {}", final_balance - initial_balance);
+    Ok(())
+}
+
+/// test_InterestAccrual
+async fn interest(fixture: &Fixture) -> Result<()> {
+    supply(fixture, &fixture.supplier, ether(10000)).await?;
+    supply_collateral(fixture, ether(1000)).await?;
+    borrow(fixture, ether(500)).await?;
+
+    let total_borrow_assets_before = market(fixture).await?.totalBorrowAssets;
+    let operation = accrueInterestCall {
+        marketParams: fixture.market_params.clone(),
+    };
+    fixture.regular.execute(fixture.morpho, operation).await?;
+    let total_borrow_assets_after = market(fixture).await?.totalBorrowAssets;
+
+    // `vm.warp` is commented out in the Solidity test, so without time passing the
+    // interest may be zero: we check the mechanism with `>=` instead of `>`.
+    ensure!(
+        total_borrow_assets_after >= total_borrow_assets_before,
+        "Interest accrual mechanism failed"
+    );
+    println!(
+        "Interest accrued: {}",
+        total_borrow_assets_after - total_borrow_assets_before
+    );
+    Ok(())
+}
+
+/// test_MultipleSuppliers
+async fn multiple_suppliers(fixture: &Fixture) -> Result<()> {
+    let amount1 = ether(1000);
+    let amount2 = ether(500);
+    supply(fixture, &fixture.supplier, amount1).await?;
+    supply(fixture, &fixture.supplier2, amount2).await?;
+
+    let market = market(fixture).await?;
+    ensure!(
+        U256::from(market.totalSupplyAssets) == amount1 + amount2,
+        "Total supply wrong"
+    );
+
+    for (user, amount) in [(&fixture.supplier, amount1), (&fixture.supplier2, amount2)] {
+        let operation = withdrawCall {
+            marketParams: fixture.market_params.clone(),
+            assets: amount,
+            shares: U256::ZERO,
+            onBehalf: user.address,
+            receiver: user.address,
+        };
+        user.execute(fixture.morpho, operation).await?;
+    }
+    for (user, amount) in [(&fixture.supplier, amount1), (&fixture.supplier2, amount2)] {
+        user.process_inbox().await?;
+        let balance = user
+            .query(
+                fixture.loan_token,
+                balanceOfCall {
+                    owner: user.address,
+                },
+            )
+            .await?;
+        ensure!(balance == amount, "Withdrawal of {} failed", user.address);
+    }
+    Ok(())
+}
+
+/// test_MaxBorrowCapacity
+async fn max_borrow(fixture: &Fixture) -> Result<()> {
+    let collateral_amount = ether(1000);
+    let max_borrow = collateral_amount * fixture.market_params.lltv / ether(1);
+    supply(fixture, &fixture.supplier, ether(10000)).await?;
+    supply_collateral(fixture, collateral_amount).await?;
+
+    borrow(fixture, max_borrow - ether(1)).await?;
+    fixture.borrower.process_inbox().await?;
+    let result = fixture
+        .borrower
+        .submit(fixture.morpho, borrow_call(fixture, ether(2)))
+        .await;
+    ensure!(
+        result.is_err(),
+        "Expected borrow to fail when exceeding max capacity, but it succeeded"
+    );
+    Ok(())
+}
+
+/// test_SupplyWithCallback
+async fn supply_callback(fixture: &Fixture) -> Result<()> {
+    let supply_amount = ether(1000);
+    let operation = setBalanceCall {
+        owner: fixture.test_contract_address,
+        amount: supply_amount,
+    };
+    fixture
+        .regular
+        .execute(fixture.loan_token, operation)
+        .await?;
+    // The callback does the approval.
+    let operation = setLoanTokenApprovalCall { amount: U256::ZERO };
+    fixture
+        .regular
+        .execute(fixture.test_contract, operation)
+        .await?;
+    reset_callback_flags(fixture).await?;
+
+    let operation = callSupplyWithCallbackCall {
+        amount: supply_amount,
+        data: callback_data("supply_callback"),
+    };
+    fixture
+        .regular
+        .execute(fixture.test_contract, operation)
+        .await?;
+    let triggered = fixture
+        .regular
+        .query(fixture.test_contract, supplyCallbackTriggeredCall {})
+        .await?;
+    ensure!(triggered, "Supply callback not triggered");
+
+    let market = market(fixture).await?;
+    ensure!(
+        U256::from(market.totalSupplyAssets) == supply_amount,
+        "Supply amount mismatch"
+    );
+    Ok(())
+}
+
+/// test_SupplyCollateralWithCallback
+async fn supply_collateral_callback(fixture: &Fixture) -> Result<()> {
+    let collateral_amount = ether(1000);
+    let operation = setBalanceCall {
+        owner: fixture.test_contract_address,
+        amount: collateral_amount,
+    };
+    fixture
+        .regular
+        .execute(fixture.collateral_token, operation)
+        .await?;
+    reset_callback_flags(fixture).await?;
+
+    let operation = callSupplyCollateralWithCallbackCall {
+        amount: collateral_amount,
+        data: callback_data("supply_collateral_callback"),
+    };
+    fixture
+        .regular
+        .execute(fixture.test_contract, operation)
+        .await?;
+    let query = supplyCollateralCallbackTriggeredCall {};
+    let triggered = fixture.regular.query(fixture.test_contract, query).await?;
+    ensure!(triggered, "Supply collateral callback not triggered");
+    Ok(())
+}
+
+/// test_RepayWithCallback
+async fn repay_callback(fixture: &Fixture) -> Result<()> {
+    let collateral_amount = ether(1000);
+    let borrow_amount = ether(600);
+    supply(fixture, &fixture.supplier, ether(10000)).await?;
+
+    // The test contract supplies collateral and borrows.
+    let operation = setBalanceCall {
+        owner: fixture.test_contract_address,
+        amount: collateral_amount,
+    };
+    fixture
+        .regular
+        .execute(fixture.collateral_token, operation)
+        .await?;
+    let operation = setCollateralTokenApprovalCall { amount: U256::MAX };
+    fixture
+        .regular
+        .execute(fixture.test_contract, operation)
+        .await?;
+    let operation = callSupplyCollateralCall {
+        amount: collateral_amount,
+    };
+    fixture
+        .regular
+        .execute(fixture.test_contract, operation)
+        .await?;
+    let operation = callBorrowCall {
+        amount: borrow_amount,
+    };
+    fixture
+        .regular
+        .execute(fixture.test_contract, operation)
+        .await?;
+    fixture.regular.process_inbox().await?;
+    let query = balanceOfCall {
+        owner: fixture.test_contract_address,
+    };
+    let balance = fixture.regular.query(fixture.loan_token, query).await?;
+    println!("Loan token balance of the test contract after borrowing: {balance}");
+
+    // The callback does the approval.
+    let operation = setLoanTokenApprovalCall { amount: U256::ZERO };
+    fixture
+        .regular
+        .execute(fixture.test_contract, operation)
+        .await?;
+    reset_callback_flags(fixture).await?;
+    println!(
+        "Total borrow assets before repaying: {}",
+        market(fixture).await?.totalBorrowAssets
+    );
+
+    let operation = callRepayWithCallbackCall {
+        amount: borrow_amount,
+        data: callback_data("repay_callback"),
+    };
+    fixture
+        .regular
+        .execute(fixture.test_contract, operation)
+        .await?;
+    let triggered = fixture
+        .regular
+        .query(fixture.test_contract, repayCallbackTriggeredCall {})
+        .await?;
+    ensure!(triggered, "Repay callback not triggered");
+
+    let market = market(fixture).await?;
+    ensure!(market.totalBorrowAssets == 0, "Debt not fully repaid");
+    Ok(())
+}
+
+/// test_LiquidateWithCallback
+async fn liquidate_callback(fixture: &Fixture) -> Result<()> {
+    supply(fixture, &fixture.supplier, ether(10000)).await?;
+    supply_collateral(fixture, ether(1000)).await?;
+    borrow(fixture, ether(700)).await?;
+    drop_price(fixture).await?;
+
+    let operation = setBalanceCall {
+        owner: fixture.test_contract_address,
+        amount: ether(1000),
+    };
+    fixture
+        .regular
+        .execute(fixture.loan_token, operation)
+        .await?;
+    reset_callback_flags(fixture).await?;
+
+    let operation = callLiquidateWithCallbackCall {
+        borrowerAddr: fixture.borrower.address,
+        seizedAssets: ether(100),
+        data: callback_data("liquidate_callback"),
+    };
+    fixture
+        .regular
+        .execute(fixture.test_contract, operation)
+        .await?;
+    let triggered = fixture
+        .regular
+        .query(fixture.test_contract, liquidateCallbackTriggeredCall {})
+        .await?;
+    ensure!(triggered, "Liquidate callback not triggered");
+    Ok(())
+}
@@ -0,0 +1,428 @@
+//! Compilation of Solidity source trees with `solc --standard-json`. The compiler is
+//! `$SOLC` if it is set, and `solc` from the `PATH` otherwise. The outputs are cached in
+//! the `.solc-cache` directory of the source tree, keyed by a hash of the compiler
+//! version, of the standard-JSON input and of all the Solidity files of the tree.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use alloy_primitives::{keccak256, Address};
+use anyhow::{bail, ensure, Context, Result};
+use serde_json::{json, Value};
+
+const CACHE_DIRECTORY: &str = ".solc-cache";
+
+pub struct Solc {
+    path: PathBuf,
+    version: String,
+}
+
+impl Solc {
+    pub fn locate() -> Result<Solc> {
+        let path = match env::var_os("SOLC") {
+            Some(path) => PathBuf::from(path),
+            None => find_in_path("solc").context("solc is not in the PATH and SOLC is not set")?,
+        };
+        let output = Command::new(&path)
+            .arg("--version")
+            .output()
+            .with_context(|| format!("failed to run {}", path.display()))?;
+        ensure!(
+            output.status.success(),
+            "{} --version failed",
+            path.display()
+        );
+        let version = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .last()
+            .unwrap_or_default()
+            .to_string();
+        tracing::info!("Using {} ({version})", path.display());
+        Ok(Solc { path, version })
+    }
+
+    /// Compiles the tree, or returns the cached output of an identical compilation.
+    pub fn compile(&self, tree: &SourceTree) -> Result<Compilation> {
+        let input = tree.standard_json();
+        let hash = tree.hash(&self.version, &input)?;
+        let cache_path = tree.root.join(CACHE_DIRECTORY).join(format!("{hash}.json"));
+        if cache_path.exists() {
+            tracing::info!("Reading the cached compilation {}", cache_path.display());
+            return Compilation::parse(&fs::read_to_string(&cache_path)?);
+        }
+        let output = self.run(&tree.root, &input)?;
+        let compilation = Compilation::parse(&output)?;
+        fs::create_dir_all(tree.root.join(CACHE_DIRECTORY))?;
+        fs::write(&cache_path, &output)?;
+        Ok(compilation)
+    }
+
+    fn run(&self, root: &Path, input: &Value) -> Result<String> {
+        let mut child = Command::new(&self.path)
+            .current_dir(root)
+            .arg("--standard-json")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to run {}", self.path.display()))?;
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.to_string().as_bytes())?;
+        let output = child.wait_with_output()?;
+        ensure!(
+            output.status.success(),
+            "solc failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8(output.stdout)?)
+    }
+}
+
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths)
+        .map(|directory| directory.join(name))
+        .find(|path| path.is_file())
+}
+
+/// The Solidity files under `directory`, relative to `root`, except the cache.
+fn solidity_files(root: &Path, directory: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if !path.ends_with(CACHE_DIRECTORY) {
+                solidity_files(root, &path, files)?;
+            }
+        } else if path.extension().is_some_and(|extension| extension == "sol") {
+            files.push(path.strip_prefix(root)?.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+/// The Solidity files to compile, with the files of `root` that they may import. As in
+/// the `config.json` of the Morpho tests, the sources are named by their file name.
+pub struct SourceTree {
+    pub root: PathBuf,
+    pub sources: Vec<PathBuf>,
+    pub via_ir: bool,
+    pub optimizer_runs: Option<u32>,
+}
+
+impl SourceTree {
+    pub fn new(root: impl Into<PathBuf>, sources: &[&str]) -> SourceTree {
+        SourceTree {
+            root: root.into(),
+            sources: sources.iter().map(PathBuf::from).collect(),
+            via_ir: true,
+            optimizer_runs: Some(200),
+        }
+    }
+
+    fn standard_json(&self) -> Value {
+        let sources = self
+            .sources
+            .iter()
+            .map(|path| {
+                let name = path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                (name, json!({ "urls": [path] }))
+            })
+            .collect::<serde_json::Map<_, _>>();
+        json!({
+            "language": "Solidity",
+            "sources": sources,
+            "settings": {
+                "viaIR": self.via_ir,
+                "optimizer": {
+                    "enabled": self.optimizer_runs.is_some(),
+                    "runs": self.optimizer_runs.unwrap_or(200),
+                },
+                "outputSelection": {
+                    "*": {
+                        "*": ["abi", "evm.bytecode"]
+                    }
+                }
+            }
+        })
+    }
+
+    fn hash(&self, version: &str, input: &Value) -> Result<String> {
+        let mut files = Vec::new();
+        solidity_files(&self.root, &self.root, &mut files)?;
+        files.sort();
+        let mut data = Vec::new();
+        for part in [version.as_bytes(), input.to_string().as_bytes()] {
+            data.extend_from_slice(part);
+            data.push(0);
+        }
+        for file in files {
+            data.extend_from_slice(file.to_string_lossy().as_bytes());
+            data.push(0);
+            data.extend(fs::read(self.root.join(&file))?);
+            data.push(0);
+        }
+        Ok(hex::encode(keccak256(&data)))
+    }
+}
+
+/// The positions in a bytecode of the address of a library, as `(file_name, name)`.
+pub struct LinkReference {
+    pub library: (String, String),
+    /// The byte offsets of the 20-byte placeholders.
+    pub offsets: Vec<usize>,
+}
+
+/// A contract of the output of solc.
+pub struct CompiledContract {
+    pub file_name: String,
+    pub name: String,
+    pub abi: Value,
+    /// The hex bytecode, with placeholders for the addresses of the libraries if any.
+    pub bytecode_object: String,
+    pub link_references: Vec<LinkReference>,
+}
+
+impl CompiledContract {
+    /// Interfaces and abstract contracts have no bytecode.
+    pub fn is_deployable(&self) -> bool {
+        !self.bytecode_object.is_empty()
+    }
+
+    /// The bytecode with the placeholders replaced by the `addresses` of the libraries.
+    pub fn link(&self, addresses: &BTreeMap<(String, String), Address>) -> Result<Vec<u8>> {
+        let mut bytecode = self.bytecode_object.clone();
+        for reference in &self.link_references {
+            let (file_name, name) = &reference.library;
+            let address = addresses.get(&reference.library).with_context(|| {
+                format!(
+                    "{} needs the library {file_name}:{name} to be linked",
+                    self.name
+                )
+            })?;
+            let address = hex::encode(address);
+            for offset in &reference.offsets {
+                let range = 2 * offset..2 * (offset + 20);
+                ensure!(
+                    bytecode
+                        .get(range.clone())
+                        .is_some_and(|placeholder| placeholder.starts_with("__$")),
+                    "no placeholder for {name} at offset {offset} in {}",
+                    self.name
+                );
+                bytecode.replace_range(range, &address);
+            }
+        }
+        Ok(hex::decode(&bytecode)?)
+    }
+}
+
+fn parse_link_references(value: &Value) -> Result<Vec<LinkReference>> {
+    let mut link_references = Vec::new();
+    let Some(files) = value.as_object() else {
+        return Ok(link_references);
+    };
+    for (file_name, libraries) in files {
+        let libraries = libraries
+            .as_object()
+            .with_context(|| format!("the link references of {file_name} are not an object"))?;
+        for (name, positions) in libraries {
+            let offsets = positions
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|position| {
+                    position["start"]
+                        .as_u64()
+                        .map(|start| start as usize)
+                        .with_context(|| format!("invalid link reference {position}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            link_references.push(LinkReference {
+                library: (file_name.clone(), name.clone()),
+                offsets,
+            });
+        }
+    }
+    Ok(link_references)
+}
+
+pub struct Compilation {
+    contracts: BTreeMap<(String, String), CompiledContract>,
+}
+
+impl Compilation {
+    fn parse(output: &str) -> Result<Compilation> {
+        let output: Value = serde_json::from_str(output)?;
+        let errors = output["errors"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|error| error["severity"] == "error")
+            .map(|error| {
+                error["formattedMessage"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            bail!("solc reported errors:\n{}", errors.join("\n"));
+        }
+        let mut contracts = BTreeMap::new();
+        let files = output["contracts"]
+            .as_object()
+            .with_context(|| format!("contracts is missing or not an object: {output}"))?;
+        for (file_name, file_contracts) in files {
+            let file_contracts = file_contracts
+                .as_object()
+                .with_context(|| format!("the contracts of {file_name} are not an object"))?;
+            for (name, data) in file_contracts {
+                let bytecode_object = data["evm"]["bytecode"]["object"]
+                    .as_str()
+                    .with_context(|| format!("failed to get the bytecode of {name} in {data}"))?
+                    .to_string();
+                let contract = CompiledContract {
+                    file_name: file_name.clone(),
+                    name: name.clone(),
+                    abi: data["abi"].clone(),
+                    bytecode_object,
+                    link_references: parse_link_references(
+                        &data["evm"]["bytecode"]["linkReferences"],
+                    )?,
+                };
+                contracts.insert((file_name.clone(), name.clone()), contract);
+            }
+        }
+        Ok(Compilation { contracts })
+    }
+
+    pub fn contract(&self, file_name: &str, name: &str) -> Result<&CompiledContract> {
+        self.contracts
+            .get(&(file_name.to_string(), name.to_string()))
+            .with_context(|| format!("{name} is not a contract of {file_name}"))
+    }
+
+    /// The contract called `name`, which must be unique among all the files.
+    pub fn contract_named(&self, name: &str) -> Result<&CompiledContract> {
+        let mut candidates = self.contracts().filter(|contract| contract.name == name);
+        let contract = candidates
+            .next()
+            .with_context(|| format!("no contract is called {name}"))?;
+        if let Some(other) = candidates.next() {
+            bail!(
+                "{name} is defined in both {} and {}",
+                contract.file_name,
+                other.file_name
+            );
+        }
+        Ok(contract)
+    }
+
+    pub fn contracts(&self) -> impl Iterator<Item = &CompiledContract> {
+        self.contracts.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLACEHOLDER: &str = "__$0123456789abcdef0123456789abcdef01$__";
+
+    fn math_library() -> (String, String) {
+        ("lib.sol".to_string(), "Math".to_string())
+    }
+
+    /// A contract calling the library `lib.sol:Math`, whose address is at byte 2.
+    fn contract_linking_math() -> CompiledContract {
+        let link_references = json!({ "lib.sol": { "Math": [{ "start": 2, "length": 20 }] } });
+        CompiledContract {
+            file_name: "main.sol".to_string(),
+            name: "Main".to_string(),
+            abi: json!([]),
+            bytecode_object: format!("6080{PLACEHOLDER}6000"),
+            link_references: parse_link_references(&link_references).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_parse_link_references() {
+        let link_references = contract_linking_math().link_references;
+        assert_eq!(link_references.len(), 1);
+        assert_eq!(link_references[0].library, math_library());
+        assert_eq!(link_references[0].offsets, [2]);
+        assert!(parse_link_references(&Value::Null).unwrap().is_empty());
+        assert!(
+            parse_link_references(&json!({ "lib.sol": { "Math": [{ "start": -1 }] } })).is_err()
+        );
+    }
+
+    #[test]
+    fn test_link_replaces_the_placeholder() {
+        let address = Address::repeat_byte(0xab);
+        let addresses = BTreeMap::from([(math_library(), address)]);
+        let bytecode = contract_linking_math().link(&addresses).unwrap();
+        let mut expected = vec![0x60, 0x80];
+        expected.extend_from_slice(address.as_slice());
+        expected.extend_from_slice(&[0x60, 0x00]);
+        assert_eq!(bytecode, expected);
+    }
+
+    #[test]
+    fn test_link_without_the_library() {
+        let error = contract_linking_math().link(&BTreeMap::new()).unwrap_err();
+        assert!(error.to_string().contains("lib.sol:Math"), "{error}");
+    }
+
+    #[test]
+    fn test_link_without_a_placeholder() {
+        let mut contract = contract_linking_math();
+        contract.link_references[0].offsets = vec![0];
+        let addresses = BTreeMap::from([(math_library(), Address::ZERO)]);
+        assert!(contract.link(&addresses).is_err());
+    }
+
+    #[test]
+    fn test_parse_compilation() {
+        let output = json!({
+            "errors": [{ "severity": "warning", "formattedMessage": "unused variable" }],
+            "contracts": {
+                "a.sol": {
+                    "Token": { "abi": [], "evm": { "bytecode": { "object": "6080", "linkReferences": {} } } },
+                    "IToken": { "abi": [], "evm": { "bytecode": { "object": "" } } }
+                },
+                "b.sol": {
+                    "Token": { "abi": [], "evm": { "bytecode": { "object": "6080" } } }
+                }
+            }
+        });
+        let compilation = Compilation::parse(&output.to_string()).unwrap();
+        assert!(compilation
+            .contract("a.sol", "Token")
+            .unwrap()
+            .is_deployable());
+        assert!(!compilation
+            .contract_named("IToken")
+            .unwrap()
+            .is_deployable());
+        assert!(compilation.contract_named("Token").is_err());
+        assert!(compilation.contract_named("Vault").is_err());
+
+        let output =
+            json!({ "errors": [{ "severity": "error", "formattedMessage": "syntax error" }] });
+        let error = Compilation::parse(&output.to_string()).err().unwrap();
+        assert!(error.to_string().contains("syntax error"), "{error}");
+    }
+}